@@ -3,15 +3,11 @@
 #![no_std]
 #![allow(unused_variables)]
 
-use cortex_m;
 use cortex_m_rt::entry;
 use rtt_target::{rprintln, rtt_init_print};
-#[cfg(feature = "stm32f412")]
-use stm32f4xx_hal::fsmc_lcd::ChipSelect1;
-#[cfg(feature = "stm32f413")]
-use stm32f4xx_hal::fsmc_lcd::ChipSelect3;
 use stm32f4xx_hal::{
-    fsmc_lcd::{FsmcLcd, LcdPins, Timing},
+    fsmc_lcd::{DataPins16, FsmcLcd, LcdPins, Timing},
+    gpio::alt::fsmc as alt,
     pac,
     prelude::*,
     rcc::Rcc,
@@ -29,9 +25,8 @@ use stm32f4xx_hal::fmpi2c::FMPI2c;
 use stm32f4xx_hal::i2c::I2c;
 
 #[allow(unused_imports)]
-use panic_semihosting;
+use panic_semihosting as _;
 
-use ft6x06;
 use st7789::*;
 
 /// A simple example to connect to the FT6x06 crate and get the touch coordinates.
@@ -56,33 +51,20 @@ fn main() -> ! {
     let gpiof = p.GPIOF.split();
     let gpiog = p.GPIOG.split();
 
-    let lcd_pins = LcdPins {
-        data: (
-            gpiod.pd14.into_alternate(),
-            gpiod.pd15.into_alternate(),
-            gpiod.pd0.into_alternate(),
-            gpiod.pd1.into_alternate(),
-            gpioe.pe7.into_alternate(),
-            gpioe.pe8.into_alternate(),
-            gpioe.pe9.into_alternate(),
-            gpioe.pe10.into_alternate(),
-            gpioe.pe11.into_alternate(),
-            gpioe.pe12.into_alternate(),
-            gpioe.pe13.into_alternate(),
-            gpioe.pe14.into_alternate(),
-            gpioe.pe15.into_alternate(),
-            gpiod.pd8.into_alternate(),
-            gpiod.pd9.into_alternate(),
-            gpiod.pd10.into_alternate(),
+    let lcd_pins = LcdPins::new(
+        DataPins16::new(
+            gpiod.pd14, gpiod.pd15, gpiod.pd0, gpiod.pd1, gpioe.pe7, gpioe.pe8, gpioe.pe9,
+            gpioe.pe10, gpioe.pe11, gpioe.pe12, gpioe.pe13, gpioe.pe14, gpioe.pe15, gpiod.pd8,
+            gpiod.pd9, gpiod.pd10,
         ),
-        address: gpiof.pf0.into_alternate(),
-        read_enable: gpiod.pd4.into_alternate(),
-        write_enable: gpiod.pd5.into_alternate(),
+        alt::Address::from(gpiof.pf0),
+        gpiod.pd4,
+        gpiod.pd5,
         #[cfg(feature = "stm32f413")]
-        chip_select: ChipSelect3(gpiog.pg10.into_alternate()),
+        alt::ChipSelect3::from(gpiog.pg10),
         #[cfg(feature = "stm32f412")]
-        chip_select: ChipSelect1(gpiod.pd7.into_alternate()),
-    };
+        alt::ChipSelect1::from(gpiod.pd7),
+    );
 
     // Setup the RESET pin
     #[cfg(feature = "stm32f413")]
@@ -150,11 +132,15 @@ fn main() -> ! {
     #[cfg(feature = "stm32f413")]
     let ts_int = gpioc.pc1.into_pull_down_input();
 
-    let mut touch = ft6x06::Ft6X06::new(&i2c, 0x38, ts_int).unwrap();
+    let panel = ft6x06::constant::FT_6X06_MAX_WIDTH_HEIGHT as u16;
+    let mut touch = ft6x06::Ft6X06::new(&i2c, 0x38, ts_int)
+        .unwrap()
+        .with_panel_size(panel, panel)
+        .unwrap();
 
     let tsc = touch.ts_calibration(&mut i2c, &mut delay);
     match tsc {
-        Err(e) => rprintln!("Error {} from ts_calibration", e),
+        Err(e) => rprintln!("Error {:?} from ts_calibration", e),
         Ok(u) => rprintln!("ts_calibration returned {}", u),
     }
     rprintln!("If nothing happens - touch the screen!");
@@ -172,7 +158,7 @@ fn main() -> ! {
         }
 
         if num > 0 {
-            let t = touch.get_touch(&mut i2c, 1);
+            let t = touch.get_touch(&mut i2c, ft6x06::TouchSlot::P1);
 
             match t {
                 Err(_e) => rprintln!("Error fetching touch data"),
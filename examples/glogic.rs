@@ -2,7 +2,6 @@
 #![no_main]
 #![no_std]
 
-use cortex_m;
 use cortex_m_rt::entry;
 use rtt_target::{rprintln, rtt_init_print};
 #[cfg(feature = "stm32f413")]
@@ -6,15 +6,11 @@
 /// A simple example to connect to the FT6x06 crate on stm32f412/3 board and get the touch data to evaluate
 /// which option is clicked by the user. This can be considered as interface
 /// for user to give an input.
-use cortex_m;
 use cortex_m_rt::entry;
 use rtt_target::{rprintln, rtt_init_print};
-#[cfg(feature = "stm32f412")]
-use stm32f4xx_hal::fsmc_lcd::ChipSelect1;
-#[cfg(feature = "stm32f413")]
-use stm32f4xx_hal::fsmc_lcd::ChipSelect3;
 use stm32f4xx_hal::{
-    fsmc_lcd::{FsmcLcd, LcdPins, Timing},
+    fsmc_lcd::{DataPins16, FsmcLcd, LcdPins, Timing},
+    gpio::alt::fsmc as alt,
     pac,
     prelude::*,
     rcc::Rcc,
@@ -34,9 +30,8 @@ use stm32f4xx_hal::fmpi2c::FMPI2c;
 use stm32f4xx_hal::i2c::I2c;
 
 #[allow(unused_imports)]
-use panic_semihosting;
+use panic_semihosting as _;
 
-use ft6x06;
 use st7789::*;
 
 #[entry]
@@ -59,33 +54,20 @@ fn main() -> ! {
     let gpiof = p.GPIOF.split();
     let gpiog = p.GPIOG.split();
 
-    let lcd_pins = LcdPins {
-        data: (
-            gpiod.pd14.into_alternate(),
-            gpiod.pd15.into_alternate(),
-            gpiod.pd0.into_alternate(),
-            gpiod.pd1.into_alternate(),
-            gpioe.pe7.into_alternate(),
-            gpioe.pe8.into_alternate(),
-            gpioe.pe9.into_alternate(),
-            gpioe.pe10.into_alternate(),
-            gpioe.pe11.into_alternate(),
-            gpioe.pe12.into_alternate(),
-            gpioe.pe13.into_alternate(),
-            gpioe.pe14.into_alternate(),
-            gpioe.pe15.into_alternate(),
-            gpiod.pd8.into_alternate(),
-            gpiod.pd9.into_alternate(),
-            gpiod.pd10.into_alternate(),
+    let lcd_pins = LcdPins::new(
+        DataPins16::new(
+            gpiod.pd14, gpiod.pd15, gpiod.pd0, gpiod.pd1, gpioe.pe7, gpioe.pe8, gpioe.pe9,
+            gpioe.pe10, gpioe.pe11, gpioe.pe12, gpioe.pe13, gpioe.pe14, gpioe.pe15, gpiod.pd8,
+            gpiod.pd9, gpiod.pd10,
         ),
-        address: gpiof.pf0.into_alternate(),
-        read_enable: gpiod.pd4.into_alternate(),
-        write_enable: gpiod.pd5.into_alternate(),
+        alt::Address::from(gpiof.pf0),
+        gpiod.pd4,
+        gpiod.pd5,
         #[cfg(feature = "stm32f413")]
-        chip_select: ChipSelect3(gpiog.pg10.into_alternate()),
+        alt::ChipSelect3::from(gpiog.pg10),
         #[cfg(feature = "stm32f412")]
-        chip_select: ChipSelect1(gpiod.pd7.into_alternate()),
-    };
+        alt::ChipSelect1::from(gpiod.pd7),
+    );
 
     // Setup the RESET pin
     #[cfg(feature = "stm32f413")]
@@ -125,15 +107,11 @@ fn main() -> ! {
         .fill_color(Rgb565::BLACK)
         .build();
 
-    Rectangle::new(Point::new(140, 80), Size::new(80, 80))
-        .into_styled(style)
-        .draw(&mut disp)
-        .unwrap();
+    let yes = Rectangle::new(Point::new(20, 80), Size::new(80, 80));
+    let no = Rectangle::new(Point::new(140, 80), Size::new(80, 80));
 
-    Rectangle::new(Point::new(20, 80), Size::new(80, 80))
-        .into_styled(style)
-        .draw(&mut disp)
-        .unwrap();
+    no.into_styled(style).draw(&mut disp).unwrap();
+    yes.into_styled(style).draw(&mut disp).unwrap();
 
     // Create a new character style
     let text_style = MonoTextStyle::new(&FONT_6X10, Rgb565::WHITE);
@@ -178,11 +156,20 @@ fn main() -> ! {
     #[cfg(feature = "stm32f413")]
     let ts_int = gpioc.pc1.into_pull_down_input();
 
-    let mut touch = ft6x06::Ft6X06::new(&i2c, 0x38, ts_int).unwrap();
+    let panel = ft6x06::constant::FT_6X06_MAX_WIDTH_HEIGHT as u16;
+    let mut touch = ft6x06::Ft6X06::new(&i2c, 0x38, ts_int)
+        .unwrap()
+        .with_panel_size(panel, panel)
+        .unwrap();
+    // The controller axes are swapped against the display in portrait mode
+    touch.set_transform(ft6x06::Transform {
+        swap_xy: true,
+        ..Default::default()
+    });
 
     let tsc = touch.ts_calibration(&mut i2c, &mut delay);
     match tsc {
-        Err(e) => rprintln!("Error {} from ts_calibration", e),
+        Err(e) => rprintln!("Error {:?} from ts_calibration", e),
         Ok(u) => rprintln!("ts_calibration returned {}", u),
     }
     rprintln!("If nothing happens - touch the screen!");
@@ -201,23 +188,15 @@ fn main() -> ! {
         }
 
         if num > 0 {
-            let t = touch.get_touch(&mut i2c, 1);
+            let t = touch.get_touch(&mut i2c, ft6x06::TouchSlot::P1);
 
             match t {
                 Err(_e) => rprintln!("Error fetching touch data"),
-                Ok(n) => {
-                    if n.x > 80 && n.x < 160 {
-                        if n.y < 100 && n.y > 20 {
-                            rprintln!("You pressed Yes");
-                        } else if n.y < 200 && n.y > 120 {
-                            rprintln!("You pressed No");
-                        } else {
-                            rprintln!("Press a key");
-                        }
-                    } else {
-                        rprintln!("Press a key");
-                    }
-                }
+                Ok(n) => match n.hit_index(&[yes, no]) {
+                    Some(0) => rprintln!("You pressed Yes"),
+                    Some(_) => rprintln!("You pressed No"),
+                    None => rprintln!("Press a key"),
+                },
             }
         }
     }
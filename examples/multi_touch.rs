@@ -4,7 +4,6 @@
 
 /// An example to use access Ft6x06 driver and get coordinates for
 /// multiple touch points.
-use cortex_m;
 use cortex_m_rt::entry;
 use rtt_target::{rprintln, rtt_init_print};
 #[cfg(feature = "stm32f413")]
@@ -14,7 +13,7 @@ use stm32f4xx_hal::i2c::I2c;
 use stm32f4xx_hal::{pac, prelude::*, rcc::Rcc};
 
 #[allow(unused_imports)]
-use panic_semihosting;
+use panic_semihosting as _;
 
 extern crate ft6x06;
 
@@ -71,10 +70,10 @@ fn main() -> ! {
 
     let mut touch = ft6x06::Ft6X06::new(&i2c, 0x38, ts_int).unwrap();
 
-    let tsc = touch.ts_calibration(&mut i2c, &mut delay);
-    match tsc {
-        Err(e) => rprintln!("Error {} from ts_calibration", e),
-        Ok(u) => rprintln!("ts_calibration returned {}", u),
+    let options = ft6x06::InitOptions::new().calibrate(true);
+    match touch.init_with(&mut i2c, &mut delay, &options) {
+        Err(e) => rprintln!("Error {:?} from init", e),
+        Ok(variant) => rprintln!("Found {:?}", variant),
     }
     rprintln!("If nothing happens - touch the screen!");
     // for _i in 0..3000 {
@@ -92,24 +91,21 @@ fn main() -> ! {
         }
 
         if num > 0 {
-            let t = touch.get_multi_touch(&mut i2c, 1);
+            let t = touch.get_multi_touch(&mut i2c);
             match t {
                 Err(_e) => rprintln!("Error fetching touch data"),
-                Ok(n) => {
-                    rprintln!(
-                        "Touch: {:>3}x{:>3} - weight: {:>3} misc: {}",
-                        n.touch_x[0],
-                        n.touch_y[0],
-                        n.touch_weight[0],
-                        n.touch_area[0],
-                    );
-                    rprintln!(
-                        "Touch: {:>3}x{:>3} - weight: {:>3} misc: {}",
-                        n.touch_x[0],
-                        n.touch_y[1],
-                        n.touch_weight[1],
-                        n.touch_area[1],
-                    )
+                Ok(touches) => {
+                    for n in touches.iter() {
+                        rprintln!(
+                            "Touch {} {:?}: {:>3}x{:>3} - weight: {:>3} area: {}",
+                            n.touch_id,
+                            n.event,
+                            n.x,
+                            n.y,
+                            n.weight,
+                            n.area(),
+                        );
+                    }
                 }
             }
         }
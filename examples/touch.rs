@@ -3,7 +3,8 @@
 
 /// A basic example to show the use of ft6x06 crate using STM32F412/3 board
 /// This example shows how to get touch coordinates.
-use cortex_m;
+/// The bus, pin and delay are driven through their embedded-hal 0.2 traits via
+/// `ft6x06::eh02::Compat`, as a board with an older HAL would have to.
 use cortex_m_rt::entry;
 use rtt_target::{rprintln, rtt_init_print};
 #[cfg(feature = "stm32f413")]
@@ -13,9 +14,10 @@ use stm32f4xx_hal::i2c::I2c;
 use stm32f4xx_hal::{pac, prelude::*, rcc::Rcc};
 
 #[allow(unused_imports)]
-use panic_semihosting;
+use panic_semihosting as _;
 
 extern crate ft6x06;
+use ft6x06::eh02::Compat;
 
 #[entry]
 fn main() -> ! {
@@ -28,7 +30,7 @@ fn main() -> ! {
     let rcc: Rcc = perif.RCC.constrain();
 
     let clocks = rcc.cfgr.sysclk(100.MHz()).freeze();
-    let mut delay = cp.SYST.delay(&clocks);
+    let mut delay = Compat(cp.SYST.delay(&clocks));
 
     rprintln!("Connecting to I2c");
 
@@ -37,7 +39,7 @@ fn main() -> ! {
 
     // STM32F412 touchscreen controller uses I2C1 module from embedded-hal.
     #[cfg(feature = "stm32f412")]
-    let mut i2c = {
+    let i2c = {
         let gpiob = perif.GPIOB.split();
         I2c::new(
             perif.I2C1,
@@ -52,7 +54,7 @@ fn main() -> ! {
 
     // STM32F413 shares the same I2C bus for both audio driver and touchscreen controller. FMPI2C module from embedded-hal is used.
     #[cfg(feature = "stm32f413")]
-    let mut i2c = {
+    let i2c = {
         FMPI2c::new(
             perif.FMPI2C1,
             (
@@ -72,11 +74,12 @@ fn main() -> ! {
     #[cfg(feature = "stm32f413")]
     let ts_int = { gpioc.pc1.into_pull_down_input() };
 
-    let mut touch = ft6x06::Ft6X06::new(&i2c, 0x38, ts_int).unwrap();
+    let mut i2c = Compat(i2c);
+    let mut touch = ft6x06::Ft6X06::new(&i2c, 0x38, Compat(ts_int)).unwrap();
 
     let tsc = touch.ts_calibration(&mut i2c, &mut delay);
     match tsc {
-        Err(e) => rprintln!("Error {} from ts_calibration", e),
+        Err(e) => rprintln!("Error {:?} from ts_calibration", e),
         Ok(u) => rprintln!("ts_calibration returned {}", u),
     }
     rprintln!("If nothing happens - touch the screen!");
@@ -95,11 +98,13 @@ fn main() -> ! {
         }
 
         if num > 0 {
-            let t = touch.get_touch(&mut i2c, 1);
+            let t = touch.get_touch(&mut i2c, ft6x06::TouchSlot::P1);
             match t {
                 Err(_e) => rprintln!("Error fetching touch data"),
                 Ok(n) => rprintln!(
-                    "Touch: {:>3}x{:>3} - weight: {:>3} misc: {}",
+                    "Touch {} {:?}: {:>3}x{:>3} - weight: {:>3} misc: {}",
+                    n.touch_id,
+                    n.event,
                     n.x,
                     n.y,
                     n.weight,
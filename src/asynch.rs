@@ -0,0 +1,710 @@
+//! Async FT6x06 driver built on `embedded-hal-async`.
+//!
+//! Instead of spinning on the INT pin or on TD_STATUS, the async driver awaits
+//! the touch interrupt, so the executor can run other tasks (or sleep) while
+//! nobody touches the screen.
+//!
+//! The driver owns its I2C bus and INT pin. To share the bus with other
+//! devices, hand it a shared-bus device such as embassy-embedded-hal's
+//! `I2cDevice`.
+//!
+//!  ### Example
+//!
+//! ```ignore
+//! let mut touch = ft6x06::asynch::Ft6X06::new(i2c, 0x38, ts_int);
+//! loop {
+//!     let touches = touch.read_touches().await?;
+//! }
+//! ```
+
+use crate::calibration::{Calibration, CalibrationError, Residual, Sample};
+use crate::constant::*;
+use crate::register::*;
+use crate::transform::Panel;
+use crate::{
+    check_panel_size, ChipVariant, DeviceConfig, DeviceInfo, Error, Ft6x06Capabilities,
+    GestureConfig, GestureKind, InitOptions, InterruptMode, MultiTouch, PowerMode, ScanConfig,
+    ThresholdStats, TouchReport, TouchSlot, TouchState, Transform,
+};
+#[cfg(feature = "embedded-graphics")]
+use embedded_graphics::geometry::{Dimensions, Point, Size};
+use embedded_hal::digital::{Error as _, OutputPin};
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::digital::Wait;
+use embedded_hal_async::i2c::I2c;
+#[cfg(feature = "storage")]
+use embedded_storage::nor_flash::ReadNorFlash;
+
+/// Async FT6x06 driver object.
+/// Owns the I2C bus and the touch interrupt pin.
+/// `N` is the number of touch points the controller tracks, as for
+/// [`crate::Ft6X06`].
+pub struct Ft6X06<I2C, TouchInterruptPin, const N: usize = FT6X06_MAX_NB_TOUCH> {
+    i2c: I2C,
+    addr: u8,
+    interrupt: TouchInterruptPin,
+    interrupt_mode: InterruptMode,
+    variant: Option<ChipVariant>,
+    panel: Panel,
+}
+
+/// Long hard reset, as [`crate::long_hard_reset`]
+async fn hard_reset<RST: OutputPin, E, PinE>(
+    rst: &mut RST,
+    delay_source: &mut impl DelayNs,
+) -> Result<(), Error<E, PinE>> {
+    rst.set_low().map_err(|e| Error::ResetPin(e.kind()))?;
+    delay_source.delay_us(10_000).await;
+    rst.set_high().map_err(|e| Error::ResetPin(e.kind()))
+}
+
+/// Async driver for FT5x06 controllers, tracking up to FT5X06_MAX_NB_TOUCH points
+pub type Ft5X06<I2C, TouchInterruptPin> = Ft6X06<I2C, TouchInterruptPin, FT5X06_MAX_NB_TOUCH>;
+
+/// Async driver for FT5336 controllers, tracking up to FT5336_MAX_NB_TOUCH points
+pub type Ft5336<I2C, TouchInterruptPin> = Ft6X06<I2C, TouchInterruptPin, FT5336_MAX_NB_TOUCH>;
+
+impl<I2C, TouchInterruptPin, E, PinE> Ft6X06<I2C, TouchInterruptPin>
+where
+    I2C: I2c<Error = E>,
+    TouchInterruptPin: Wait<Error = PinE>,
+{
+    /// Creates a new sensor on the given I2C bus and interrupt pin.
+    pub fn new(i2c: I2C, addr: u8, interrupt: TouchInterruptPin) -> Self {
+        Self::with_max_touch(i2c, addr, interrupt)
+    }
+}
+
+impl<I2C, TouchInterruptPin, E, PinE, const N: usize> Ft6X06<I2C, TouchInterruptPin, N>
+where
+    I2C: I2c<Error = E>,
+    TouchInterruptPin: Wait<Error = PinE>,
+{
+    /// Creates a new sensor tracking up to `N` touch points on the given
+    /// I2C bus and interrupt pin.
+    pub fn with_max_touch(i2c: I2C, addr: u8, interrupt: TouchInterruptPin) -> Self {
+        const { assert!(N >= 1 && N <= FT5336_MAX_NB_TOUCH) };
+        Ft6X06 {
+            i2c,
+            addr,
+            interrupt,
+            interrupt_mode: InterruptMode::default(),
+            variant: None,
+            panel: Panel::DEFAULT,
+        }
+    }
+
+    /// Transform applied to every touch point
+    pub fn transform(&self) -> Transform {
+        self.panel.transform
+    }
+
+    /// Change the transform applied to every touch point, e.g. when the UI
+    /// rotates. Mirroring and rotation flip coordinates against the panel
+    /// size, so set it with [`with_panel_size`](Self::with_panel_size) first.
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.panel.transform = transform;
+    }
+
+    /// Calibration applied to every touch point
+    pub fn calibration(&self) -> Option<Calibration> {
+        self.panel.calibration
+    }
+
+    /// Change the calibration applied to every touch point
+    pub fn set_calibration(&mut self, calibration: Option<Calibration>) {
+        self.panel.calibration = calibration;
+    }
+
+    /// Fit a calibration to samples read with
+    /// [`get_raw_touch`](Self::get_raw_touch) whose targets are display
+    /// positions under the current transform, see [`crate::calibration`]
+    pub fn fit_calibration(
+        &self,
+        samples: &[Sample],
+    ) -> Result<(Calibration, Residual), CalibrationError> {
+        self.panel.fit_calibration(samples)
+    }
+
+    /// Scale a touch point from the panel into the bounding box of
+    /// `target`, e.g. when the panel and the display differ in resolution
+    #[cfg(feature = "embedded-graphics")]
+    pub fn map_to_target<D: Dimensions>(&self, touch: &TouchState, target: &D) -> Point {
+        let (width, height) = self.panel.output_size();
+        crate::graphics::scale_to_area(
+            touch,
+            Size::new(u32::from(width), u32::from(height)),
+            &target.bounding_box(),
+        )
+    }
+
+    /// Set the size of the panel the controller is mounted on.
+    /// Reported coordinates are clamped to it. Without a panel size the
+    /// driver passes the full 12 bit coordinate range through unclamped.
+    pub fn with_panel_size(mut self, width: u16, height: u16) -> Result<Self, Error<E, PinE>> {
+        check_panel_size(width, height)?;
+        self.panel.width = width;
+        self.panel.height = height;
+        Ok(self)
+    }
+
+    /// Destroy the driver and give back the I2C bus and interrupt pin
+    pub fn release(self) -> (I2C, TouchInterruptPin) {
+        (self.i2c, self.interrupt)
+    }
+
+    /// Bring the controller up after power-on: wait out the start-up time,
+    /// check the chip ID and detect the variant, then set it up as
+    /// `options` say. Returns the detected variant.
+    pub async fn init_with(
+        &mut self,
+        delay_source: &mut impl DelayNs,
+        options: &InitOptions,
+    ) -> Result<ChipVariant, Error<E, PinE>> {
+        delay_source.delay_ms(FT6X06_STARTUP_TIME_MS).await;
+        let variant = self.detect_variant().await?;
+        if let Some(config) = &options.config {
+            self.apply_config(config).await?;
+        }
+        if let Some(mode) = options.interrupt_mode {
+            self.set_interrupt_mode(mode).await?;
+        } else if options.config.is_none() {
+            // G_MODE stays as it is, but wait_touch_interrupt needs to know it
+            self.interrupt_mode().await?;
+        }
+        if options.calibrate {
+            self.ts_calibration(delay_source).await?;
+        }
+        Ok(variant)
+    }
+
+    /// Like [`init_with`](Self::init_with), after a long hard reset through `rst`
+    pub async fn reset_and_init<RST: OutputPin>(
+        &mut self,
+        rst: &mut RST,
+        delay_source: &mut impl DelayNs,
+        options: &InitOptions,
+    ) -> Result<ChipVariant, Error<E, PinE>> {
+        hard_reset(rst, delay_source).await?;
+        self.init_with(delay_source, options).await
+    }
+
+    /// Like [`init_with`](Self::init_with), with the calibration and config
+    /// of the settings record at `offset` in `flash`, see [`crate::storage`].
+    /// Returns the detected variant and whether the record was used.
+    #[cfg(feature = "storage")]
+    pub async fn init_from_storage<S: ReadNorFlash>(
+        &mut self,
+        delay_source: &mut impl DelayNs,
+        flash: &mut S,
+        offset: u32,
+        options: &InitOptions,
+    ) -> Result<(ChipVariant, bool), Error<E, PinE>> {
+        let (options, calibration, restored) = crate::storage::restore(flash, offset, options);
+        self.set_calibration(calibration);
+        let variant = self.init_with(delay_source, &options).await?;
+        Ok((variant, restored))
+    }
+
+    /// Returns the structure that contains all the preset capabilities
+    /// of the detected variant, see [`detect_variant`](Self::detect_variant)
+    pub fn get_capabilities(&self) -> Ft6x06Capabilities {
+        let (width, height) = self.panel.output_size();
+        self.variant().capabilities().with_panel(width, height)
+    }
+
+    /// Variant found by the last [`detect_variant`](Self::detect_variant),
+    /// [`ChipVariant::Ft6206`] before that
+    pub fn variant(&self) -> ChipVariant {
+        self.variant.unwrap_or_default()
+    }
+
+    /// Whether the controller has a gesture engine. Until the variant is
+    /// detected it is assumed to have one.
+    fn has_gestures(&self) -> bool {
+        self.variant.is_none_or(|v| v.capabilities().gesture())
+    }
+
+    /// Number of slots holding touch data: `N`, or fewer once a variant
+    /// that tracks fewer points is detected
+    fn slot_count(&self) -> usize {
+        self.variant
+            .map_or(N, |v| N.min(v.capabilities().max_touch() as usize))
+    }
+
+    /// Identify the controller variant and use its capabilities from then on.
+    /// Fails with [`Error::TooManyTouchPoints`] if the variant tracks more
+    /// than `N` touch points.
+    pub async fn detect_variant(&mut self) -> Result<ChipVariant, Error<E, PinE>> {
+        let id = self.read_register::<ChipId>().await?.value();
+        let cipher = self.read_register::<Cipher>().await?.value();
+        let variant = ChipVariant::detect(id, cipher).ok_or(Error::WrongChipId(id))?;
+        if variant.capabilities().max_touch() as usize > N {
+            return Err(Error::TooManyTouchPoints(variant));
+        }
+        self.variant = Some(variant);
+        Ok(variant)
+    }
+
+    /// Read whether the FT6X06 is in dev mode or not
+    pub async fn dev_mode_r(&mut self) -> Result<u8, Error<E, PinE>> {
+        let dev_mode: DevMode = self.read_register().await?;
+        Ok(dev_mode.mode())
+    }
+
+    /// Put the FT6X06 into dev mode
+    pub async fn dev_mode_w(&mut self, value: u8) -> Result<bool, Error<E, PinE>> {
+        // No read-back: calibration moves the controller out of factory mode on its own.
+        self.modify_register::<DevMode>(|r| r.set_mode(value), false)
+            .await?;
+
+        Ok(value == 0)
+    }
+
+    /// Get the value of an 8 bit register
+    pub async fn get_u8_reg(&mut self, reg: u8) -> Result<u8, Error<E, PinE>> {
+        let mut ibuf: [u8; 1] = [0];
+        self.read_regs(reg, &mut ibuf).await?;
+        Ok(ibuf[0])
+    }
+
+    /// Set the value of an 8 bit register
+    pub async fn set_u8_reg(&mut self, reg: u8, val: u8) -> Result<(), Error<E, PinE>> {
+        self.i2c
+            .write(self.addr, &[reg, val])
+            .await
+            .map_err(Error::Bus)
+    }
+
+    /// Read a typed register
+    pub async fn read_register<R: Register>(&mut self) -> Result<R, Error<E, PinE>> {
+        Ok(R::from_bits(self.get_u8_reg(R::ADDR).await?))
+    }
+
+    /// Write a typed register
+    pub async fn write_register<R: Writable>(&mut self, reg: R) -> Result<(), Error<E, PinE>> {
+        self.set_u8_reg(R::ADDR, reg.bits()).await
+    }
+
+    /// Read-modify-write a typed register: `f` sets fields of the value read,
+    /// the other fields are kept. With `verify` the register is read back and
+    /// [`Error::ReadBack`] is returned if it did not take the value.
+    /// Returns the value written.
+    pub async fn modify_register<R: Writable>(
+        &mut self,
+        f: impl FnOnce(&mut R),
+        verify: bool,
+    ) -> Result<R, Error<E, PinE>> {
+        let mut reg: R = self.read_register().await?;
+        f(&mut reg);
+        self.write_register(reg).await?;
+        if verify {
+            let read = self.get_u8_reg(R::ADDR).await?;
+            if read != reg.bits() {
+                return Err(Error::ReadBack {
+                    reg: R::ADDR,
+                    wrote: reg.bits(),
+                    read,
+                });
+            }
+        }
+        Ok(reg)
+    }
+
+    /// Read-modify-write a register: the bits under `mask` are replaced by
+    /// those of `value`, the others are kept. Returns the value written.
+    pub async fn modify_reg(&mut self, reg: u8, mask: u8, value: u8) -> Result<u8, Error<E, PinE>> {
+        let old = self.get_u8_reg(reg).await?;
+        let new = (old & !mask) | (value & mask);
+        self.set_u8_reg(reg, new).await?;
+        Ok(new)
+    }
+
+    /// Write the field `mask`/`shift` of a register, leaving the other bits as
+    /// they are. With `verify` the register is read back and
+    /// [`Error::ReadBack`] is returned if the field did not take the value.
+    pub async fn write_field(
+        &mut self,
+        reg: u8,
+        mask: u8,
+        shift: u8,
+        value: u8,
+        verify: bool,
+    ) -> Result<(), Error<E, PinE>> {
+        let wrote = self.modify_reg(reg, mask, value << shift).await?;
+        if verify {
+            let read = self.get_u8_reg(reg).await?;
+            if read & mask != wrote & mask {
+                return Err(Error::ReadBack { reg, wrote, read });
+            }
+        }
+        Ok(())
+    }
+
+    /// Read consecutive registers starting at `reg` in one transfer
+    async fn read_regs(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Error<E, PinE>> {
+        self.i2c
+            .write_read(self.addr, &[reg], buf)
+            .await
+            .map_err(Error::Bus)
+    }
+
+    /// Read the interrupt mode from the controller. The driver also uses the
+    /// result for [`wait_touch_interrupt`](Self::wait_touch_interrupt) from then on.
+    pub async fn interrupt_mode(&mut self) -> Result<InterruptMode, Error<E, PinE>> {
+        let g_mode: GMode = self.read_register().await?;
+        let mode = g_mode.interrupt_mode().ok_or(Error::UnexpectedValue {
+            reg: GMode::ADDR,
+            value: g_mode.bits(),
+        })?;
+        self.interrupt_mode = mode;
+        Ok(mode)
+    }
+
+    /// Select how the controller drives the INT pin
+    pub async fn set_interrupt_mode(&mut self, mode: InterruptMode) -> Result<(), Error<E, PinE>> {
+        self.modify_register::<GMode>(|r| r.set_interrupt_mode(mode), true)
+            .await?;
+        self.interrupt_mode = mode;
+        Ok(())
+    }
+
+    /// Wait for the touchscreen interrupt to indicate touches.
+    ///
+    /// In [`InterruptMode::Polling`] this returns as soon as INT is low, i.e.
+    /// at once while a touch is held. In [`InterruptMode::Trigger`] it waits
+    /// for the falling edge of the next pulse.
+    pub async fn wait_touch_interrupt(&mut self) -> Result<(), Error<E, PinE>> {
+        match self.interrupt_mode {
+            InterruptMode::Polling => self.interrupt.wait_for_low().await,
+            InterruptMode::Trigger => self.interrupt.wait_for_falling_edge().await,
+        }
+        .map_err(Error::Pin)
+    }
+
+    /// Run an internal calibration on the FT6X06
+    pub async fn ts_calibration(
+        &mut self,
+        delay_source: &mut impl DelayNs,
+    ) -> Result<bool, Error<E, PinE>> {
+        self.dev_mode_w(FT6X06_DEV_MODE_FACTORY).await?;
+
+        delay_source.delay_ms(300).await;
+
+        for _attempt in 0..100 {
+            if self.dev_mode_r().await? == FT6X06_DEV_MODE_WORKING {
+                return Ok(true);
+            }
+            delay_source.delay_ms(200).await;
+        }
+        Err(Error::CalibrationTimeout)
+    }
+
+    /// Read the touch device status
+    pub async fn td_status(&mut self) -> Result<u8, Error<E, PinE>> {
+        let td_status: TdStatus = self.read_register().await?;
+        Ok(td_status.touch_points())
+    }
+
+    /// Read the touch device chip ID. Fails unless it is FT6X06_ID, FT6X36_ID or
+    /// FT5336_ID.
+    pub async fn chip_id(&mut self) -> Result<u8, Error<E, PinE>> {
+        let id = self.read_register::<ChipId>().await?.value();
+        if !matches!(id, FT6X06_ID | FT6X36_ID | FT5336_ID) {
+            return Err(Error::WrongChipId(id));
+        }
+        Ok(id)
+    }
+
+    /// Read the chip ID, firmware, library and vendor versions in one
+    /// transfer. The chip ID is reported as read, not checked.
+    pub async fn device_info(&mut self) -> Result<DeviceInfo, Error<E, PinE>> {
+        let mut buf = [0; FT6X06_INFO_LEN];
+        self.read_regs(FT6X06_LIB_VER_H_REG, &mut buf).await?;
+        Ok(DeviceInfo::from_registers(&buf))
+    }
+
+    /// Read the current power mode
+    pub async fn power_mode(&mut self) -> Result<PowerMode, Error<E, PinE>> {
+        let pwr_mode: PwrMode = self.read_register().await?;
+        pwr_mode.power_mode().ok_or(Error::UnexpectedValue {
+            reg: PwrMode::ADDR,
+            value: pwr_mode.bits(),
+        })
+    }
+
+    /// Switch the power mode. Returns whether the controller changed state.
+    ///
+    /// A hibernating controller does not answer on I2C, so entering
+    /// Hibernate cannot be read back and leaving it needs [`wake`](Self::wake).
+    pub async fn set_power_mode(&mut self, mode: PowerMode) -> Result<bool, Error<E, PinE>> {
+        if self.power_mode().await? == mode {
+            return Ok(false);
+        }
+
+        let mut pwr_mode = PwrMode::default();
+        pwr_mode.set_power_mode(mode);
+        self.write_register(pwr_mode).await?;
+
+        if mode == PowerMode::Hibernate {
+            return Ok(true);
+        }
+        Ok(self.power_mode().await? == mode)
+    }
+
+    /// Wake the controller from Hibernate with a long hard reset and wait
+    /// until it answers again. Returns whether it came back in Active mode.
+    pub async fn wake<RST: OutputPin>(
+        &mut self,
+        rst: &mut RST,
+        delay_source: &mut impl DelayNs,
+    ) -> Result<bool, Error<E, PinE>> {
+        hard_reset(rst, delay_source).await?;
+        delay_source.delay_ms(FT6X06_STARTUP_TIME_MS).await;
+        Ok(self.power_mode().await? == PowerMode::Active)
+    }
+
+    /// Read the scan-rate and auto-monitor configuration
+    pub async fn read_scan_config(&mut self) -> Result<ScanConfig, Error<E, PinE>> {
+        Ok(ScanConfig {
+            active_rate: self.read_register::<PeriodActive>().await?.value(),
+            monitor_rate: self.read_register::<PeriodMonitor>().await?.value(),
+            monitor_timeout_s: self.read_register::<TimeEnterMonitor>().await?.value(),
+            keep_active: !self.read_register::<Ctrl>().await?.auto_monitor(),
+        })
+    }
+
+    /// Write the scan-rate and auto-monitor configuration, verifying every
+    /// register. Values outside the datasheet ranges are rejected with
+    /// [`Error::OutOfRange`] before anything is written.
+    pub async fn apply_scan_config(&mut self, config: &ScanConfig) -> Result<(), Error<E, PinE>> {
+        config
+            .validate()
+            .map_err(|(reg, value)| Error::OutOfRange { reg, value })?;
+
+        self.modify_register::<PeriodActive>(|r| r.set_value(config.active_rate), true)
+            .await?;
+        self.modify_register::<PeriodMonitor>(|r| r.set_value(config.monitor_rate), true)
+            .await?;
+        self.modify_register::<TimeEnterMonitor>(|r| r.set_value(config.monitor_timeout_s), true)
+            .await?;
+        self.modify_register::<Ctrl>(|r| r.set_auto_monitor(!config.keep_active), true)
+            .await?;
+        Ok(())
+    }
+
+    /// Read the gesture recognition parameters
+    pub async fn read_gesture_config(&mut self) -> Result<GestureConfig, Error<E, PinE>> {
+        Ok(GestureConfig {
+            radian: self.read_register::<RadianValue>().await?.value(),
+            offset_left_right: self.read_register::<OffsetLeftRight>().await?.value(),
+            offset_up_down: self.read_register::<OffsetUpDown>().await?.value(),
+            distance_left_right: self.read_register::<DistanceLeftRight>().await?.value(),
+            distance_up_down: self.read_register::<DistanceUpDown>().await?.value(),
+            distance_zoom: self.read_register::<DistanceZoom>().await?.value(),
+        })
+    }
+
+    /// Write the gesture recognition parameters, verifying every register
+    pub async fn apply_gesture_config(
+        &mut self,
+        config: &GestureConfig,
+    ) -> Result<(), Error<E, PinE>> {
+        self.modify_register::<RadianValue>(|r| r.set_value(config.radian), true)
+            .await?;
+        self.modify_register::<OffsetLeftRight>(|r| r.set_value(config.offset_left_right), true)
+            .await?;
+        self.modify_register::<OffsetUpDown>(|r| r.set_value(config.offset_up_down), true)
+            .await?;
+        self.modify_register::<DistanceLeftRight>(
+            |r| r.set_value(config.distance_left_right),
+            true,
+        )
+        .await?;
+        self.modify_register::<DistanceUpDown>(|r| r.set_value(config.distance_up_down), true)
+            .await?;
+        self.modify_register::<DistanceZoom>(|r| r.set_value(config.distance_zoom), true)
+            .await?;
+        Ok(())
+    }
+
+    /// Snapshot every configurable register
+    pub async fn read_config(&mut self) -> Result<DeviceConfig, Error<E, PinE>> {
+        Ok(DeviceConfig {
+            threshold: self.threshold().await?,
+            filter_coefficient: self.filter_coefficient().await?,
+            scan: self.read_scan_config().await?,
+            interrupt_mode: self.interrupt_mode().await?,
+            gesture: if self.has_gestures() {
+                self.read_gesture_config().await?
+            } else {
+                GestureConfig::default()
+            },
+        })
+    }
+
+    /// Restore a snapshot taken with [`read_config`](Self::read_config),
+    /// verifying every register
+    pub async fn apply_config(&mut self, config: &DeviceConfig) -> Result<(), Error<E, PinE>> {
+        self.apply_scan_config(&config.scan).await?;
+        self.set_threshold(config.threshold).await?;
+        self.set_filter_coefficient(config.filter_coefficient)
+            .await?;
+        self.set_interrupt_mode(config.interrupt_mode).await?;
+        if self.has_gestures() {
+            self.apply_gesture_config(&config.gesture).await?;
+        }
+        Ok(())
+    }
+
+    /// Read the touch detection threshold
+    pub async fn threshold(&mut self) -> Result<u8, Error<E, PinE>> {
+        Ok(self.read_register::<ThGroup>().await?.threshold())
+    }
+
+    /// Set the touch detection threshold. A lower threshold detects lighter
+    /// touches, e.g. through thick cover glass.
+    pub async fn set_threshold(&mut self, threshold: u8) -> Result<(), Error<E, PinE>> {
+        self.modify_register::<ThGroup>(|r| r.set_threshold(threshold), true)
+            .await?;
+        Ok(())
+    }
+
+    /// Read the filter function coefficient
+    pub async fn filter_coefficient(&mut self) -> Result<u8, Error<E, PinE>> {
+        Ok(self.read_register::<ThDiff>().await?.value())
+    }
+
+    /// Set the filter function coefficient
+    pub async fn set_filter_coefficient(&mut self, value: u8) -> Result<(), Error<E, PinE>> {
+        self.modify_register::<ThDiff>(|r| r.set_value(value), true)
+            .await?;
+        Ok(())
+    }
+
+    /// Step through `thresholds`, sampling `samples` scans `interval_ms`
+    /// apart at each one, and hand the detection statistics of every
+    /// threshold to `report`. The threshold in use before the sweep is
+    /// restored afterwards, also when the sweep fails; the error of the
+    /// sweep takes precedence over one from restoring.
+    pub async fn sweep_threshold(
+        &mut self,
+        thresholds: impl IntoIterator<Item = u8>,
+        samples: u16,
+        interval_ms: u32,
+        delay_source: &mut impl DelayNs,
+        mut report: impl FnMut(ThresholdStats),
+    ) -> Result<(), Error<E, PinE>> {
+        let original = self.threshold().await?;
+        let result = self
+            .run_sweep(thresholds, samples, interval_ms, delay_source, &mut report)
+            .await;
+        let restored = self.set_threshold(original).await;
+        result.and(restored)
+    }
+
+    async fn run_sweep(
+        &mut self,
+        thresholds: impl IntoIterator<Item = u8>,
+        samples: u16,
+        interval_ms: u32,
+        delay_source: &mut impl DelayNs,
+        report: &mut impl FnMut(ThresholdStats),
+    ) -> Result<(), Error<E, PinE>> {
+        for threshold in thresholds {
+            self.set_threshold(threshold).await?;
+            let mut stats = ThresholdStats::new(threshold);
+            for _ in 0..samples {
+                delay_source.delay_ms(interval_ms).await;
+                stats.add(&self.read_report().await?);
+            }
+            report(stats);
+        }
+        Ok(())
+    }
+
+    /// Retrieve the FT6X06 firmware id
+    pub async fn firmware_id(&mut self) -> Result<u8, Error<E, PinE>> {
+        Ok(self.read_register::<FirmId>().await?.value())
+    }
+
+    /// Retrieve the Gesture Init variable
+    pub async fn gesture_radian_read(&mut self) -> Result<u8, Error<E, PinE>> {
+        Ok(self.read_register::<RadianValue>().await?.value())
+    }
+
+    /// Write the Gesture Init variable
+    pub async fn gesture_radian_write(&mut self, value: u8) -> Result<bool, Error<E, PinE>> {
+        self.modify_register::<RadianValue>(|r| r.set_value(value), true)
+            .await?;
+        Ok(value == 0)
+    }
+
+    /// Fetch the touch data of the given slot
+    pub async fn get_touch(&mut self, slot: TouchSlot<N>) -> Result<TouchState, Error<E, PinE>> {
+        Ok(self.get_raw_touch(slot).await?.to_panel(&self.panel))
+    }
+
+    /// Fetch the touch data of the given slot in raw controller coordinates:
+    /// no calibration, clamping or transform. Use it to take calibration
+    /// samples. Fails with [`Error::InvalidSlot`] for a slot past the points
+    /// the detected variant tracks.
+    pub async fn get_raw_touch(
+        &mut self,
+        slot: TouchSlot<N>,
+    ) -> Result<TouchState, Error<E, PinE>> {
+        if slot.index() >= self.slot_count() {
+            return Err(Error::InvalidSlot(slot.index()));
+        }
+        let mut buf: [u8; 6] = [0; 6];
+        self.read_regs(slot.xh_reg(), &mut buf).await?;
+        Ok(TouchState::from_registers(&buf))
+    }
+
+    /// Fetch the touch points that are present, read together with the
+    /// touch count in one transfer
+    pub async fn get_multi_touch(&mut self) -> Result<MultiTouch<N>, Error<E, PinE>> {
+        let report = self.read_report().await?;
+        Ok(MultiTouch::from_points(report.points()))
+    }
+
+    /// Read the gesture, the touch count and the touch points of one scan
+    /// in a single I2C transfer
+    pub async fn read_report(&mut self) -> Result<TouchReport<N>, Error<E, PinE>> {
+        let mut buf = [0; FT6X06_MAX_REPORT_LEN];
+        let buf = &mut buf[..TouchReport::<N>::LEN];
+        self.read_regs(FT6X06_DEV_MODE_REG, buf).await?;
+        let mut report = TouchReport::from_registers(buf).map_err(Error::InvalidTouchCount)?;
+        if !self.has_gestures() {
+            report.gesture = GestureKind::None;
+        }
+        Ok(report.to_panel(&self.panel))
+    }
+
+    /// Get gestures interpreted by touchscreen
+    pub async fn get_gesture(&mut self) -> Result<GestureKind, Error<E, PinE>> {
+        if !self.has_gestures() {
+            return Ok(GestureKind::None);
+        }
+        let gest_id: GestId = self.read_register().await?;
+        Ok(gest_id.gesture())
+    }
+
+    /// Read the touch points without waiting. When nobody touches the screen
+    /// the result is returned at once and is empty.
+    pub async fn try_read_touches(&mut self) -> Result<MultiTouch<N>, Error<E, PinE>> {
+        self.get_multi_touch().await
+    }
+
+    /// Sleep until the controller raises its interrupt and reports at least
+    /// one touch, then read the touch points.
+    pub async fn read_touches(&mut self) -> Result<MultiTouch<N>, Error<E, PinE>> {
+        loop {
+            self.wait_touch_interrupt().await?;
+            let touches = self.get_multi_touch().await?;
+            if !touches.is_empty() {
+                return Ok(touches);
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Affine touch calibration.
+//!
+//! The controller's coordinate range rarely matches the panel pixels, and
+//! assembly tolerances add offset and skew. A [`Calibration`] maps raw
+//! controller coordinates onto display pixels with an affine matrix in
+//! Q16.16 fixed point:
+//!
+//! ```text
+//! x' = a * x + b * y + c
+//! y' = d * x + e * y + f
+//! ```
+//!
+//! The calibration is the first step applied to the raw coordinates: it maps
+//! them onto the pixels of the panel along the controller axes, which are
+//! then clamped to the panel size and mapped through the
+//! [`Transform`](crate::Transform). It therefore also takes care of a
+//! controller range that differs from the panel resolution.
+//!
+//! The matrix is fitted to samples: raw touches on known display positions.
+//! Three samples give an exact fit, more give a least-squares fit. Read the
+//! touches with `get_raw_touch`, which skips calibration, clamping and
+//! transform, and fit them with the driver's `fit_calibration`, which takes
+//! the targets in display coordinates and maps them back through the
+//! transform:
+//!
+//! ```ignore
+//! // let the user touch three or more crosshairs, collecting samples
+//! let touch_pt = touch.get_raw_touch(&mut i2c, TouchSlot::P1)?;
+//! samples[n] = Sample { touch: touch_pt, target_x: 20, target_y: 20 };
+//! // ...
+//! let (calibration, residual) = touch.fit_calibration(&samples)?;
+//! touch.set_calibration(Some(calibration));
+//! ```
+//!
+//! [`Calibration::from_samples`] fits targets that are already given along
+//! the controller axes; without a transform that is the same thing.
+
+use crate::TouchState;
+
+/// Number of fraction bits of the fixed-point coefficients
+pub const CALIBRATION_FRAC_BITS: u32 = 16;
+
+/// Minimum number of samples for a fit
+pub const CALIBRATION_MIN_SAMPLES: usize = 3;
+
+/// A touch on a known display position
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Sample {
+    /// Touch as read with `get_raw_touch`, in raw controller coordinates
+    pub touch: TouchState,
+    /// Display x of the position that was touched
+    pub target_x: u16,
+    /// Display y of the position that was touched
+    pub target_y: u16,
+}
+
+/// Why no calibration could be fitted
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CalibrationError {
+    /// Fewer than CALIBRATION_MIN_SAMPLES samples
+    TooFewSamples,
+    /// The touches lie on one line, so they do not fix the matrix
+    Degenerate,
+    /// The fitted coefficients do not fit the fixed-point format
+    Overflow,
+}
+
+/// How far the calibrated samples are off their targets, in display pixels
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Residual {
+    /// Largest distance of a sample from its target
+    pub max: u16,
+    /// Root mean square distance of the samples from their targets
+    pub rms: u16,
+}
+
+/// Affine map from controller coordinates to display pixels
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Calibration {
+    coefficients: [i32; 6],
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Calibration {
+    /// Calibration that keeps the coordinates as they are
+    pub const IDENTITY: Calibration = Calibration {
+        coefficients: [
+            1 << CALIBRATION_FRAC_BITS,
+            0,
+            0,
+            0,
+            1 << CALIBRATION_FRAC_BITS,
+            0,
+        ],
+    };
+
+    /// Build a calibration from its coefficients `[a, b, c, d, e, f]` in
+    /// Q16.16 fixed point
+    pub fn from_coefficients(coefficients: [i32; 6]) -> Self {
+        Calibration { coefficients }
+    }
+
+    /// The coefficients `[a, b, c, d, e, f]` in Q16.16 fixed point
+    pub fn coefficients(&self) -> [i32; 6] {
+        self.coefficients
+    }
+
+    /// Fit a calibration to the samples and report how well it fits them.
+    /// The targets are panel pixels along the controller axes.
+    pub fn from_samples(samples: &[Sample]) -> Result<(Self, Residual), CalibrationError> {
+        Self::fit(samples, |sample| (sample.target_x, sample.target_y))
+    }
+
+    /// Fit a calibration to the samples, with `target` giving the position
+    /// each sample should map to
+    pub(crate) fn fit(
+        samples: &[Sample],
+        target: impl Fn(&Sample) -> (u16, u16),
+    ) -> Result<(Self, Residual), CalibrationError> {
+        if samples.len() < CALIBRATION_MIN_SAMPLES {
+            return Err(CalibrationError::TooFewSamples);
+        }
+
+        // Normal equations of the least-squares fit, shared by both rows
+        let mut m = [[0i128; 3]; 3];
+        let mut rx = [0i128; 3];
+        let mut ry = [0i128; 3];
+        for sample in samples {
+            let v = [i128::from(sample.touch.x), i128::from(sample.touch.y), 1];
+            let (target_x, target_y) = target(sample);
+            for (i, vi) in v.iter().enumerate() {
+                for (j, vj) in v.iter().enumerate() {
+                    m[i][j] += vi * vj;
+                }
+                rx[i] += vi * i128::from(target_x);
+                ry[i] += vi * i128::from(target_y);
+            }
+        }
+
+        let det = det3(&m);
+        if det == 0 {
+            return Err(CalibrationError::Degenerate);
+        }
+
+        let mut coefficients = [0; 6];
+        for (row, rhs) in [rx, ry].iter().enumerate() {
+            for col in 0..3 {
+                let mut mk = m;
+                for (i, r) in rhs.iter().enumerate() {
+                    mk[i][col] = *r;
+                }
+                let q = div_round(det3(&mk) << CALIBRATION_FRAC_BITS, det);
+                coefficients[row * 3 + col] =
+                    i32::try_from(q).map_err(|_| CalibrationError::Overflow)?;
+            }
+        }
+
+        let calibration = Calibration { coefficients };
+        let residual = calibration.residual_with(samples, target);
+        Ok((calibration, residual))
+    }
+
+    /// Map a point in controller coordinates onto display pixels.
+    /// Results outside the u16 range are saturated.
+    pub fn apply(&self, x: u16, y: u16) -> (u16, u16) {
+        let (x, y) = self.apply_wide(x, y);
+        (saturate(x), saturate(y))
+    }
+
+    /// How far the calibrated samples are off their targets, given along
+    /// the controller axes as for [`from_samples`](Self::from_samples)
+    pub fn residual(&self, samples: &[Sample]) -> Residual {
+        self.residual_with(samples, |sample| (sample.target_x, sample.target_y))
+    }
+
+    fn residual_with(
+        &self,
+        samples: &[Sample],
+        target: impl Fn(&Sample) -> (u16, u16),
+    ) -> Residual {
+        if samples.is_empty() {
+            return Residual::default();
+        }
+
+        let mut max = 0;
+        let mut sum = 0;
+        for sample in samples {
+            let (x, y) = self.apply_wide(sample.touch.x, sample.touch.y);
+            let (target_x, target_y) = target(sample);
+            let dx = (x - i64::from(target_x)).unsigned_abs();
+            let dy = (y - i64::from(target_y)).unsigned_abs();
+            let squared = dx * dx + dy * dy;
+            max = max.max(squared);
+            sum += squared;
+        }
+        Residual {
+            max: saturate(max.isqrt() as i64),
+            rms: saturate((sum / samples.len() as u64).isqrt() as i64),
+        }
+    }
+
+    fn apply_wide(&self, x: u16, y: u16) -> (i64, i64) {
+        let [a, b, c, d, e, f] = self.coefficients.map(i64::from);
+        let (x, y) = (i64::from(x), i64::from(y));
+        let half = 1 << (CALIBRATION_FRAC_BITS - 1);
+        (
+            (a * x + b * y + c + half) >> CALIBRATION_FRAC_BITS,
+            (d * x + e * y + f + half) >> CALIBRATION_FRAC_BITS,
+        )
+    }
+}
+
+fn det3(m: &[[i128; 3]; 3]) -> i128 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+/// Division rounding to the nearest integer
+fn div_round(n: i128, d: i128) -> i128 {
+    let (n, d) = if d < 0 { (-n, -d) } else { (n, d) };
+    if n >= 0 {
+        (n + d / 2) / d
+    } else {
+        (n - d / 2) / d
+    }
+}
+
+fn saturate(v: i64) -> u16 {
+    v.clamp(0, i64::from(u16::MAX)) as u16
+}
@@ -0,0 +1,173 @@
+//! Configuration of the FT6x06 scanning behaviour.
+
+use crate::constant::*;
+use crate::{InterruptMode, TouchReport};
+
+/// Scan-rate and auto-monitor configuration.
+///
+/// Trades touch latency against power: a high active rate reacts faster,
+/// switching to Monitor mode early saves power while nobody touches the screen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ScanConfig {
+    /// Report rate in Active mode, FT6X06_PERIODACTIVE_MIN..=FT6X06_PERIODACTIVE_MAX
+    pub active_rate: u8,
+    /// Report rate in Monitor mode, FT6X06_PERIODMONITOR_MIN..=FT6X06_PERIODMONITOR_MAX
+    pub monitor_rate: u8,
+    /// Seconds without touching before switching to Monitor mode
+    pub monitor_timeout_s: u8,
+    /// Stay in Active mode instead of switching to Monitor mode
+    pub keep_active: bool,
+}
+
+impl ScanConfig {
+    /// Check the values against the datasheet ranges.
+    /// Fails with the register and value that are out of range.
+    pub(crate) fn validate(&self) -> Result<(), (u8, u8)> {
+        if !(FT6X06_PERIODACTIVE_MIN..=FT6X06_PERIODACTIVE_MAX).contains(&self.active_rate) {
+            return Err((FT6X06_PERIODACTIVE_REG, self.active_rate));
+        }
+        if !(FT6X06_PERIODMONITOR_MIN..=FT6X06_PERIODMONITOR_MAX).contains(&self.monitor_rate) {
+            return Err((FT6X06_PERIODMONITOR_REG, self.monitor_rate));
+        }
+        Ok(())
+    }
+}
+
+/// Gesture recognition parameters.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GestureConfig {
+    /// Minimum allowed angle while Rotating gesture mode
+    pub radian: u8,
+    /// Maximum offset while Moving Left and Moving Right gesture
+    pub offset_left_right: u8,
+    /// Maximum offset while Moving Up and Moving Down gesture
+    pub offset_up_down: u8,
+    /// Minimum distance while Moving Left and Moving Right gesture
+    pub distance_left_right: u8,
+    /// Minimum distance while Moving Up and Moving Down gesture
+    pub distance_up_down: u8,
+    /// Maximum distance while Zoom In and Zoom Out gesture
+    pub distance_zoom: u8,
+}
+
+/// Snapshot of every configurable register of the controller.
+///
+/// The controller forgets its configuration on reset. Take a snapshot with
+/// `read_config` once it is tuned and restore it with `apply_config` after
+/// every reset to get back to exactly the same state.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DeviceConfig {
+    /// Touch detection threshold
+    pub threshold: u8,
+    /// Filter function coefficient
+    pub filter_coefficient: u8,
+    /// Scan rates and auto-monitor
+    pub scan: ScanConfig,
+    /// How the controller drives the INT pin
+    pub interrupt_mode: InterruptMode,
+    /// Gesture recognition parameters
+    pub gesture: GestureConfig,
+}
+
+/// What [`init_with`](crate::Ft6X06::init_with) does after waiting out the
+/// start-up time and checking the chip ID.
+///
+/// ```ignore
+/// let options = InitOptions::new()
+///     .interrupt_mode(InterruptMode::Trigger)
+///     .config(tuned)
+///     .calibrate(true);
+/// let variant = touch.reset_and_init(&mut i2c, &mut rst, &mut delay, &options)?;
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InitOptions {
+    pub(crate) interrupt_mode: Option<InterruptMode>,
+    pub(crate) config: Option<DeviceConfig>,
+    pub(crate) calibrate: bool,
+}
+
+impl Default for InitOptions {
+    fn default() -> Self {
+        InitOptions {
+            interrupt_mode: None,
+            config: None,
+            calibrate: FT6X06_AUTO_CALIBRATION_ENABLED,
+        }
+    }
+}
+
+impl InitOptions {
+    /// No interrupt mode, no configuration, calibration as set by
+    /// FT6X06_AUTO_CALIBRATION_ENABLED
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interrupt mode to set. It is set after the configuration, so it takes
+    /// precedence over the interrupt mode stored in it. Without it the mode
+    /// of the configuration applies, or, without a configuration, G_MODE is
+    /// left as it is.
+    pub fn interrupt_mode(mut self, mode: InterruptMode) -> Self {
+        self.interrupt_mode = Some(mode);
+        self
+    }
+
+    /// Configuration to apply, e.g. a snapshot taken with `read_config`
+    pub fn config(mut self, config: DeviceConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Whether to run the internal calibration last
+    pub fn calibrate(mut self, calibrate: bool) -> Self {
+        self.calibrate = calibrate;
+        self
+    }
+}
+
+/// Detection statistics collected at one threshold of a threshold sweep.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ThresholdStats {
+    /// Touch threshold the samples were taken with
+    pub threshold: u8,
+    /// Number of scans sampled
+    pub samples: u16,
+    /// Scans that reported at least one touch point
+    pub touched: u16,
+    /// Scans that reported two touch points
+    pub multi_touched: u16,
+    /// Highest touch weight seen
+    pub peak_weight: u8,
+}
+
+impl ThresholdStats {
+    pub(crate) fn new(threshold: u8) -> Self {
+        ThresholdStats {
+            threshold,
+            ..Default::default()
+        }
+    }
+
+    /// Count one scan into the statistics
+    pub(crate) fn add<const N: usize>(&mut self, report: &TouchReport<N>) {
+        self.samples += 1;
+        let points = report.points();
+        if !points.is_empty() {
+            self.touched += 1;
+        }
+        if points.len() > 1 {
+            self.multi_touched += 1;
+        }
+        for point in points {
+            self.peak_weight = self.peak_weight.max(point.weight);
+        }
+    }
+
+    /// Share of the sampled scans that detected a touch, in percent
+    pub fn detection_rate(&self) -> u8 {
+        if self.samples == 0 {
+            return 0;
+        }
+        (u32::from(self.touched) * 100 / u32::from(self.samples)) as u8
+    }
+}
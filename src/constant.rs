@@ -1,8 +1,8 @@
-/// Maximum border values of the touchscreen pad///
-pub const FT_6X06_MAX_WIDTH: u16 = 800;
+// Maximum border values of the touchscreen pad///
 /// Touchscreen pad max width  ///
-pub const FT_6X06_MAX_HEIGHT: u16 = 480;
+pub const FT_6X06_MAX_WIDTH: u16 = 800;
 /// Touchscreen pad max height ///
+pub const FT_6X06_MAX_HEIGHT: u16 = 480;
 
 /// Touchscreen pad max width and height values for FT6x36 Touch
 pub const FT_6X06_MAX_WIDTH_HEIGHT: u8 = 240;
@@ -19,7 +19,7 @@ pub const FT6X06_I2C_INITIALIZED: u8 = 1;
 pub const FT6X06_MAX_DETECTABLE_TOUCH: u8 = 2;
 
 /////
-/// @brief : Definitions for FT6X06 I2C register addresses on 8 bit
+// @brief : Definitions for FT6X06 I2C register addresses on 8 bit
 //////
 
 /// Current mode register of the FT6X06 (R/W)///
@@ -29,8 +29,9 @@ pub const FT6X06_DEV_MODE_REG: u8 = 0x00;
 pub const FT6X06_DEV_MODE_WORKING: u8 = 0x00;
 pub const FT6X06_DEV_MODE_FACTORY: u8 = 0x04;
 
-pub const FT6X06_DEV_MODE_MASK: u8 = 0x7;
+/// Device mode field: bits 6..4 of FT6X06_DEV_MODE_REG///
 pub const FT6X06_DEV_MODE_SHIFT: u8 = 4;
+pub const FT6X06_DEV_MODE_MASK: u8 = 0x07 << FT6X06_DEV_MODE_SHIFT;
 
 /// Gesture ID register///
 pub const FT6X06_GEST_ID_REG: u8 = 0x01;
@@ -60,6 +61,10 @@ pub const FT6X06_TOUCH_EVT_FLAG_NO_EVENT: u8 = 0x03;
 pub const FT6X06_TOUCH_EVT_FLAG_SHIFT: u8 = 6;
 pub const FT6X06_TOUCH_EVT_FLAG_MASK: u8 = 3 << FT6X06_TOUCH_EVT_FLAG_SHIFT;
 
+/// Touch ID, in the top nibble of Pn_YH///
+pub const FT6X06_TOUCH_ID_SHIFT: u8 = 4;
+pub const FT6X06_TOUCH_ID_MASK: u8 = 0x0F << FT6X06_TOUCH_ID_SHIFT;
+
 pub const FT6X06_MSB_MASK: u8 = 0x0F;
 pub const FT6X06_MSB_SHIFT: u8 = 0;
 
@@ -83,8 +88,8 @@ pub const FT6X06_TOUCH_WEIGHT_SHIFT: u8 = 0;
 pub const FT6X06_P1_MISC_REG: u8 = 0x08;
 
 /// Values related to FT6X06_Pn_MISC_REG///
-pub const FT6X06_TOUCH_AREA_MASK: u8 = 0x04 << 4;
 pub const FT6X06_TOUCH_AREA_SHIFT: u8 = 0x04;
+pub const FT6X06_TOUCH_AREA_MASK: u8 = 0x0F << FT6X06_TOUCH_AREA_SHIFT;
 
 pub const FT6X06_P2_XH_REG: u8 = 0x09;
 pub const FT6X06_P2_XL_REG: u8 = 0x0A;
@@ -93,6 +98,15 @@ pub const FT6X06_P2_YL_REG: u8 = 0x0C;
 pub const FT6X06_P2_WEIGHT_REG: u8 = 0x0D;
 pub const FT6X06_P2_MISC_REG: u8 = 0x0E;
 
+/// Number of registers of one touch point, Pn_XH through Pn_MISC.
+/// Point n starts at FT6X06_P1_XH_REG + (n - 1) * FT6X06_POINT_LEN on every
+/// FT6x06 and FT5x06 family part///
+pub const FT6X06_POINT_LEN: usize = 6;
+
+/// Length of the longest report, FT5336_MAX_NB_TOUCH touch points///
+pub const FT6X06_MAX_REPORT_LEN: usize =
+    FT6X06_P1_XH_REG as usize + FT5336_MAX_NB_TOUCH * FT6X06_POINT_LEN;
+
 /// Threshold for touch detection///
 pub const FT6X06_TH_GROUP_REG: u8 = 0x80;
 
@@ -106,7 +120,10 @@ pub const FT6X06_TH_DIFF_REG: u8 = 0x85;
 /// Control register///
 pub const FT6X06_CTRL_REG: u8 = 0x86;
 
-/// Values related to FT6X06_CTRL_REG///
+pub const FT6X06_CTRL_MODE_MASK: u8 = 0x01;
+pub const FT6X06_CTRL_MODE_SHIFT: u8 = 0x00;
+
+// Values related to FT6X06_CTRL_REG///
 
 /// Will keep the Active mode when there is no touching///
 pub const FT6X06_CTRL_KEEP_ACTIVE_MODE: u8 = 0x00;
@@ -120,9 +137,17 @@ pub const FT6X06_TIMEENTERMONITOR_REG: u8 = 0x87;
 /// Report rate in Active mode///
 pub const FT6X06_PERIODACTIVE_REG: u8 = 0x88;
 
+/// Valid range of FT6X06_PERIODACTIVE_REG///
+pub const FT6X06_PERIODACTIVE_MIN: u8 = 3;
+pub const FT6X06_PERIODACTIVE_MAX: u8 = 14;
+
 /// Report rate in Monitor mode///
 pub const FT6X06_PERIODMONITOR_REG: u8 = 0x89;
 
+/// Valid range of FT6X06_PERIODMONITOR_REG: 0 would stop scanning///
+pub const FT6X06_PERIODMONITOR_MIN: u8 = 1;
+pub const FT6X06_PERIODMONITOR_MAX: u8 = 0xFF;
+
 /// The value of the minimum allowed angle while Rotating gesture mode///
 pub const FT6X06_RADIAN_VALUE_REG: u8 = 0x91;
 
@@ -160,9 +185,20 @@ pub const FT6X06_G_MODE_INTERRUPT_SHIFT: u8 = 0x00;
 pub const FT6X06_G_MODE_INTERRUPT_POLLING: u8 = 0x00;
 pub const FT6X06_G_MODE_INTERRUPT_TRIGGER: u8 = 0x01;
 
-/// Current power mode the FT6X06 system is in (R)///
+/// Current power mode the FT6X06 system is in (R/W)///
 pub const FT6X06_PWR_MODE_REG: u8 = 0xA5;
 
+/// Possible values of FT6X06_PWR_MODE_REG///
+pub const FT6X06_PWR_MODE_ACTIVE: u8 = 0x00;
+pub const FT6X06_PWR_MODE_MONITOR: u8 = 0x01;
+pub const FT6X06_PWR_MODE_HIBERNATE: u8 = 0x03;
+
+///  Possible values of FT6X06_CIPHER_REG on parts with FT6X06_ID///
+pub const FT6206_CIPHER: u8 = 0x06;
+pub const FT6236_CIPHER: u8 = 0x36;
+pub const FT6336U_CIPHER: u8 = 0x64;
+pub const FT5X06_CIPHER: u8 = 0x55;
+
 /// FT6X06 firmware version///
 pub const FT6X06_FIRMID_REG: u8 = 0xA6;
 
@@ -176,6 +212,9 @@ pub const FT6X36_ID_VALUE: u8 = 0xCD;
 /// Release code version///
 pub const FT6X06_RELEASE_CODE_ID_REG: u8 = 0xAF;
 
+/// Length of the identification block, FT6X06_LIB_VER_H_REG through FT6X06_RELEASE_CODE_ID_REG///
+pub const FT6X06_INFO_LEN: usize = (FT6X06_RELEASE_CODE_ID_REG - FT6X06_LIB_VER_H_REG) as usize + 1;
+
 /// Current operating mode the FT6X06 system is in (R)///
 pub const FT6X06_STATE_REG: u8 = 0xBC;
 
@@ -184,15 +223,29 @@ pub const FT6X06_ERROR: i8 = -1;
 
 /// Max detectable simultaneous touches
 pub const FT6X06_MAX_NB_TOUCH: usize = 2;
+pub const FT5X06_MAX_NB_TOUCH: usize = 5;
+pub const FT5336_MAX_NB_TOUCH: usize = 10;
 
 /// Touch FT6XX6 IDs
 pub const FT6X06_ID: u8 = 0x11;
 pub const FT6X36_ID: u8 = 0xCD;
+pub const FT5336_ID: u8 = 0x51;
+
+/// Largest coordinate the 12 bit Pn_XH/Pn_XL and Pn_YH/Pn_YL fields can hold
+pub const FT6X06_MAX_COORDINATE: u16 = 0x0FFF;
 
 pub const FT6X06_MAX_X_LENGTH: u16 = 800_u16;
 pub const FT6X06_MAX_Y_LENGTH: u16 = 480_u16;
 pub const FT6X06_P1_XH_TP_BIT_MASK: u8 = 0x0F;
 pub const FT6X06_P1_YH_TP_BIT_MASK: u8 = 0x0F;
-pub const FT6X06_DEV_MODE_BIT_MASK: u8 = 0x70;
-pub const FT6X06_DEV_MODE_BIT_POSITION: u8 = 4;
+#[deprecated(note = "use FT6X06_DEV_MODE_MASK")]
+pub const FT6X06_DEV_MODE_BIT_MASK: u8 = FT6X06_DEV_MODE_MASK;
+#[deprecated(note = "use FT6X06_DEV_MODE_SHIFT")]
+pub const FT6X06_DEV_MODE_BIT_POSITION: u8 = FT6X06_DEV_MODE_SHIFT;
 pub const FT6X06_AUTO_CALIBRATION_ENABLED: bool = false;
+
+/// Time from releasing reset until the controller answers on I2C, in ms
+pub const FT6X06_STARTUP_TIME_MS: u32 = 300;
+
+/// Polling period of the timeout-bounded waits, in ms
+pub const FT6X06_POLL_INTERVAL_MS: u32 = 1;
@@ -0,0 +1,170 @@
+//! Adapters for peripherals that only implement the embedded-hal 0.2 traits.
+//!
+//! The driver is written against embedded-hal 1.0. Boards whose HAL still
+//! implements 0.2 (like the stm32f4xx-hal releases used by the STM32F412/F413
+//! examples) can wrap their I2C bus, pins and delay source in [`Compat`]:
+//!
+//! ```ignore
+//! let mut i2c = Compat(i2c);
+//! let mut touch = ft6x06::Ft6X06::new(&i2c, 0x38, Compat(ts_int)).unwrap();
+//! ```
+
+use embedded_hal as hal;
+use embedded_hal_02 as hal02;
+
+/// Wrapper that implements the embedded-hal 1.0 traits on top of an
+/// embedded-hal 0.2 peripheral.
+#[derive(Debug)]
+pub struct Compat<T>(pub T);
+
+impl<T> Compat<T> {
+    /// Give back the wrapped 0.2 peripheral
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Error of a wrapped embedded-hal 0.2 peripheral.
+/// 0.2 errors carry no kind, so they are all reported as `Other`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CompatError<E> {
+    /// Error of the wrapped peripheral
+    Hal(E),
+    /// An I2C transaction that 0.2 cannot do without a STOP between the
+    /// operations. Only a single write, a single read, or a write followed
+    /// by a read are supported.
+    UnsupportedTransaction,
+}
+
+impl<E: core::fmt::Debug> hal::i2c::Error for CompatError<E> {
+    fn kind(&self) -> hal::i2c::ErrorKind {
+        hal::i2c::ErrorKind::Other
+    }
+}
+
+impl<E: core::fmt::Debug> hal::digital::Error for CompatError<E> {
+    fn kind(&self) -> hal::digital::ErrorKind {
+        hal::digital::ErrorKind::Other
+    }
+}
+
+impl<T, E> hal::i2c::ErrorType for Compat<T>
+where
+    T: hal02::blocking::i2c::WriteRead<Error = E>
+        + hal02::blocking::i2c::Write<Error = E>
+        + hal02::blocking::i2c::Read<Error = E>,
+    E: core::fmt::Debug,
+{
+    type Error = CompatError<E>;
+}
+
+impl<T, E> hal::i2c::I2c for Compat<T>
+where
+    T: hal02::blocking::i2c::WriteRead<Error = E>
+        + hal02::blocking::i2c::Write<Error = E>
+        + hal02::blocking::i2c::Read<Error = E>,
+    E: core::fmt::Debug,
+{
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [hal::i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        use hal::i2c::Operation;
+
+        // Not every 0.2 HAL implements Transactional, so map the transactions
+        // that have a 0.2 counterpart and refuse the rest rather than split
+        // them with a STOP in between.
+        match operations {
+            [] => Ok(()),
+            [Operation::Write(write)] => self.0.write(address, write),
+            [Operation::Read(read)] => self.0.read(address, read),
+            [Operation::Write(write), Operation::Read(read)] => {
+                self.0.write_read(address, write, read)
+            }
+            _ => return Err(CompatError::UnsupportedTransaction),
+        }
+        .map_err(CompatError::Hal)
+    }
+
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        self.0.write(address, write).map_err(CompatError::Hal)
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.0
+            .write_read(address, write, read)
+            .map_err(CompatError::Hal)
+    }
+}
+
+impl<T, E> hal::digital::ErrorType for Compat<T>
+where
+    T: hal02::digital::v2::InputPin<Error = E>,
+    E: core::fmt::Debug,
+{
+    type Error = CompatError<E>;
+}
+
+impl<T, E> hal::digital::InputPin for Compat<T>
+where
+    T: hal02::digital::v2::InputPin<Error = E>,
+    E: core::fmt::Debug,
+{
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        self.0.is_high().map_err(CompatError::Hal)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.0.is_low().map_err(CompatError::Hal)
+    }
+}
+
+/// Output pins are wrapped separately: a 0.2 pin type can implement both
+/// `InputPin` and `OutputPin`, which would give `Compat` two error types.
+#[derive(Debug)]
+pub struct CompatOutput<T>(pub T);
+
+impl<T, E> hal::digital::ErrorType for CompatOutput<T>
+where
+    T: hal02::digital::v2::OutputPin<Error = E>,
+    E: core::fmt::Debug,
+{
+    type Error = CompatError<E>;
+}
+
+impl<T, E> hal::digital::OutputPin for CompatOutput<T>
+where
+    T: hal02::digital::v2::OutputPin<Error = E>,
+    E: core::fmt::Debug,
+{
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.set_low().map_err(CompatError::Hal)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.set_high().map_err(CompatError::Hal)
+    }
+}
+
+impl<T> hal::delay::DelayNs for Compat<T>
+where
+    T: hal02::blocking::delay::DelayUs<u32> + hal02::blocking::delay::DelayMs<u32>,
+{
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.delay_us(ns.div_ceil(1_000));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.0.delay_us(us);
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.0.delay_ms(ms);
+    }
+}
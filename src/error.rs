@@ -0,0 +1,61 @@
+//! Error type returned by the FT6x06 driver.
+
+use crate::ChipVariant;
+
+/// All errors that can occur when talking to the FT6x06.
+///
+/// `I2cE` is the error type of the I2C bus and `PinE` the error type of the
+/// touch interrupt pin.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error<I2cE, PinE> {
+    /// I2C bus transfer failed
+    Bus(I2cE),
+    /// Reading the touch interrupt pin failed
+    Pin(PinE),
+    /// Driving the reset pin failed
+    ResetPin(embedded_hal::digital::ErrorKind),
+    /// The chip ID register holds an ID this driver does not support
+    WrongChipId(u8),
+    /// The detected variant tracks more touch points than the driver's `N`;
+    /// use a driver type that matches it, e.g. [`Ft5336`](crate::Ft5336)
+    TooManyTouchPoints(ChipVariant),
+    /// The slot, by zero-based index, is past the touch points the detected
+    /// variant tracks
+    InvalidSlot(usize),
+    /// The controller did not return to working mode after calibration
+    CalibrationTimeout,
+    /// TD_STATUS reported more touches than the controller can track
+    InvalidTouchCount(u8),
+    /// A register holds a value the datasheet does not define
+    UnexpectedValue {
+        /// Register address
+        reg: u8,
+        /// Value read
+        value: u8,
+    },
+    /// A value to be written is outside the range the datasheet allows
+    OutOfRange {
+        /// Register address
+        reg: u8,
+        /// Rejected value
+        value: u8,
+    },
+    /// The panel size is zero or larger than the controller can report
+    InvalidPanelSize {
+        /// Requested width
+        width: u16,
+        /// Requested height
+        height: u16,
+    },
+    /// Nothing happened before the timeout expired
+    Timeout,
+    /// A register did not hold the written value when read back
+    ReadBack {
+        /// Register address
+        reg: u8,
+        /// Value written
+        wrote: u8,
+        /// Value read back
+        read: u8,
+    },
+}
@@ -0,0 +1,340 @@
+//! Smoothing of touch coordinates.
+//!
+//! Raw coordinates jitter by a few pixels while a finger rests on the panel.
+//! A [`Filter`] smooths the positions of one finger; [`TouchFilter`] keeps
+//! one filter per touch ID and resets it when the finger lifts.
+//!
+//! ```ignore
+//! let mut filter = TouchFilter::<_, 2>::new(
+//!     MedianFilter::<3>::new().then(OneEuroFilter::new(1.0, 0.01, 1.0)),
+//! );
+//! loop {
+//!     let t = filter.apply(touch.get_touch(&mut i2c, TouchSlot::P1)?, now_ms());
+//! }
+//! ```
+//!
+//! Filters are applied to the coordinates as the driver reports them, after
+//! calibration and transform.
+
+use crate::constant::FT6X06_MAX_NB_TOUCH;
+use crate::{MultiTouch, TouchEvent, TouchState};
+
+/// Smoothing of the positions of one finger
+pub trait Filter {
+    /// Feed the next position, `dt_ms` milliseconds after the previous one,
+    /// and get the smoothed position. `dt_ms` is 0 for the first position
+    /// after a reset.
+    fn update(&mut self, x: u16, y: u16, dt_ms: u32) -> (u16, u16);
+
+    /// Forget the positions seen so far
+    fn reset(&mut self);
+
+    /// Feed the output of this filter into `next`
+    fn then<F: Filter>(self, next: F) -> Chain<Self, F>
+    where
+        Self: Sized,
+    {
+        Chain {
+            first: self,
+            second: next,
+        }
+    }
+}
+
+/// Two filters applied one after the other, see [`Filter::then`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Filter, B: Filter> Filter for Chain<A, B> {
+    fn update(&mut self, x: u16, y: u16, dt_ms: u32) -> (u16, u16) {
+        let (x, y) = self.first.update(x, y, dt_ms);
+        self.second.update(x, y, dt_ms)
+    }
+
+    fn reset(&mut self) {
+        self.first.reset();
+        self.second.reset();
+    }
+}
+
+/// Median of the last `W` positions, per axis.
+///
+/// Removes single-sample spikes without smearing edges; adds a lag of
+/// about `W / 2` samples.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MedianFilter<const W: usize> {
+    xs: [u16; W],
+    ys: [u16; W],
+    len: usize,
+    next: usize,
+}
+
+impl<const W: usize> Default for MedianFilter<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize> MedianFilter<W> {
+    /// Median filter over a window of `W` positions
+    pub fn new() -> Self {
+        const { assert!(W >= 1) }
+        MedianFilter {
+            xs: [0; W],
+            ys: [0; W],
+            len: 0,
+            next: 0,
+        }
+    }
+}
+
+impl<const W: usize> Filter for MedianFilter<W> {
+    fn update(&mut self, x: u16, y: u16, _dt_ms: u32) -> (u16, u16) {
+        self.xs[self.next] = x;
+        self.ys[self.next] = y;
+        self.next = (self.next + 1) % W;
+        self.len = (self.len + 1).min(W);
+        (median(&self.xs, self.len), median(&self.ys, self.len))
+    }
+
+    fn reset(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+}
+
+/// Median of the first `len` values; the window is filled from the start
+fn median<const W: usize>(values: &[u16; W], len: usize) -> u16 {
+    let mut sorted = *values;
+    let sorted = &mut sorted[..len];
+    sorted.sort_unstable();
+    sorted[len / 2]
+}
+
+/// Exponential smoothing with a fixed weight: each output moves `alpha` of
+/// the way from the previous output towards the new position.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IirFilter {
+    alpha: f32,
+    x: LowPass,
+    y: LowPass,
+}
+
+impl IirFilter {
+    /// IIR filter with weight `alpha` in 0.0..=1.0 for the new position.
+    /// Smaller values smooth more and lag more.
+    pub fn new(alpha: f32) -> Self {
+        IirFilter {
+            alpha: alpha.clamp(0.0, 1.0),
+            x: LowPass::default(),
+            y: LowPass::default(),
+        }
+    }
+}
+
+impl Filter for IirFilter {
+    fn update(&mut self, x: u16, y: u16, _dt_ms: u32) -> (u16, u16) {
+        (
+            to_coordinate(self.x.update(f32::from(x), self.alpha)),
+            to_coordinate(self.y.update(f32::from(y), self.alpha)),
+        )
+    }
+
+    fn reset(&mut self) {
+        self.x = LowPass::default();
+        self.y = LowPass::default();
+    }
+}
+
+/// One-Euro filter: a low-pass filter whose cutoff rises with the speed of
+/// the finger, so a resting finger is smoothed strongly while a moving one
+/// follows with little lag.
+///
+/// Tune `min_cutoff` first, with the finger at rest, until the jitter is
+/// gone; then raise `beta` until fast moves no longer lag.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OneEuroFilter {
+    min_cutoff: f32,
+    beta: f32,
+    d_cutoff: f32,
+    x: OneEuroAxis,
+    y: OneEuroAxis,
+}
+
+impl OneEuroFilter {
+    /// One-Euro filter with the minimum cutoff frequency in Hz, the speed
+    /// coefficient `beta` and the cutoff frequency of the speed estimate in
+    /// Hz (1.0 is the usual choice)
+    pub fn new(min_cutoff: f32, beta: f32, d_cutoff: f32) -> Self {
+        OneEuroFilter {
+            min_cutoff,
+            beta,
+            d_cutoff,
+            x: OneEuroAxis::default(),
+            y: OneEuroAxis::default(),
+        }
+    }
+}
+
+impl Filter for OneEuroFilter {
+    fn update(&mut self, x: u16, y: u16, dt_ms: u32) -> (u16, u16) {
+        let dt = dt_ms as f32 / 1000.0;
+        let (min_cutoff, beta, d_cutoff) = (self.min_cutoff, self.beta, self.d_cutoff);
+        (
+            to_coordinate(self.x.update(f32::from(x), dt, min_cutoff, beta, d_cutoff)),
+            to_coordinate(self.y.update(f32::from(y), dt, min_cutoff, beta, d_cutoff)),
+        )
+    }
+
+    fn reset(&mut self) {
+        self.x = OneEuroAxis::default();
+        self.y = OneEuroAxis::default();
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct OneEuroAxis {
+    value: LowPass,
+    speed: LowPass,
+    last: Option<f32>,
+}
+
+impl OneEuroAxis {
+    fn update(&mut self, v: f32, dt: f32, min_cutoff: f32, beta: f32, d_cutoff: f32) -> f32 {
+        let speed = match self.last {
+            Some(last) if dt > 0.0 => (v - last) / dt,
+            _ => 0.0,
+        };
+        self.last = Some(v);
+        let speed = self.speed.update(speed, smoothing(d_cutoff, dt));
+        let cutoff = min_cutoff + beta * speed.abs();
+        self.value.update(v, smoothing(cutoff, dt))
+    }
+}
+
+/// Weight of the new sample for a first-order low-pass with the given
+/// cutoff frequency, sampled every `dt` seconds
+fn smoothing(cutoff: f32, dt: f32) -> f32 {
+    let r = 2.0 * core::f32::consts::PI * cutoff * dt;
+    r / (r + 1.0)
+}
+
+/// First-order low-pass, starting at the first sample
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct LowPass {
+    value: Option<f32>,
+}
+
+impl LowPass {
+    fn update(&mut self, v: f32, alpha: f32) -> f32 {
+        let value = match self.value {
+            Some(prev) => prev + alpha * (v - prev),
+            None => v,
+        };
+        self.value = Some(value);
+        value
+    }
+}
+
+fn to_coordinate(v: f32) -> u16 {
+    // `as` saturates, adding 0.5 rounds the non-negative values
+    (v + 0.5) as u16
+}
+
+/// A filter per finger, keyed by touch ID.
+///
+/// Tracks up to `N` fingers at once. The filter of a finger is reset when
+/// it reports [`TouchEvent::LiftUp`], so the next touch starts fresh.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TouchFilter<F, const N: usize = FT6X06_MAX_NB_TOUCH> {
+    slots: [FilterSlot<F>; N],
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct FilterSlot<F> {
+    touch_id: Option<u8>,
+    last_ms: u32,
+    filter: F,
+}
+
+impl<F: Filter + Clone, const N: usize> TouchFilter<F, N> {
+    /// Use a copy of `filter` for each finger
+    pub fn new(filter: F) -> Self {
+        TouchFilter {
+            slots: core::array::from_fn(|_| FilterSlot {
+                touch_id: None,
+                last_ms: 0,
+                filter: filter.clone(),
+            }),
+        }
+    }
+}
+
+impl<F: Filter, const N: usize> TouchFilter<F, N> {
+    /// Smooth the position of a touch read at `now_ms`, a millisecond
+    /// timestamp that may wrap. Touches that are not detected pass as they
+    /// are; a touch is passed unfiltered when `N` other fingers are
+    /// already tracked.
+    pub fn apply(&mut self, touch: TouchState, now_ms: u32) -> TouchState {
+        if !touch.detected {
+            return touch;
+        }
+
+        let index = match self.slot_of(touch.touch_id) {
+            Some(index) => index,
+            None => match self.slots.iter().position(|s| s.touch_id.is_none()) {
+                Some(index) => index,
+                None => return touch,
+            },
+        };
+        let slot = &mut self.slots[index];
+        let dt_ms = match slot.touch_id {
+            Some(_) => now_ms.wrapping_sub(slot.last_ms),
+            None => 0,
+        };
+        let (x, y) = slot.filter.update(touch.x, touch.y, dt_ms);
+        slot.touch_id = Some(touch.touch_id);
+        slot.last_ms = now_ms;
+
+        if touch.event == TouchEvent::LiftUp {
+            self.release(index);
+        }
+        TouchState { x, y, ..touch }
+    }
+
+    /// Smooth all points of a multi-touch read at `now_ms`. Fingers missing
+    /// from `touches` are treated as lifted.
+    pub fn apply_multi<const M: usize>(&mut self, touches: &mut MultiTouch<M>, now_ms: u32) {
+        for index in 0..N {
+            if let Some(id) = self.slots[index].touch_id {
+                if !touches.iter().any(|pt| pt.touch_id == id) {
+                    self.release(index);
+                }
+            }
+        }
+        let len = touches.len;
+        for pt in touches.points[..len].iter_mut() {
+            *pt = self.apply(*pt, now_ms);
+        }
+    }
+
+    /// Forget all fingers
+    pub fn reset(&mut self) {
+        for index in 0..N {
+            self.release(index);
+        }
+    }
+
+    fn slot_of(&self, touch_id: u8) -> Option<usize> {
+        self.slots.iter().position(|s| s.touch_id == Some(touch_id))
+    }
+
+    fn release(&mut self, index: usize) {
+        let slot = &mut self.slots[index];
+        slot.touch_id = None;
+        slot.filter.reset();
+    }
+}
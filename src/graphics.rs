@@ -0,0 +1,70 @@
+//! Glue between touch points and `embedded-graphics`.
+//!
+//! Touch points convert into [`Point`]s and can be hit-tested against any
+//! shape that implements [`ContainsPoint`], such as a [`Rectangle`] or a
+//! `Circle`. Set the [`Transform`](crate::Transform) on the driver so the
+//! touch points come in display coordinates.
+//!
+//! ```ignore
+//! let yes = Rectangle::new(Point::new(20, 80), Size::new(80, 80));
+//! let no = Rectangle::new(Point::new(140, 80), Size::new(80, 80));
+//! let t = touch.get_touch(&mut i2c, TouchSlot::P1)?;
+//! match t.hit_index(&[yes, no]) {
+//!     Some(0) => rprintln!("You pressed Yes"),
+//!     Some(_) => rprintln!("You pressed No"),
+//!     None => rprintln!("Press a key"),
+//! }
+//! ```
+//!
+//! When the panel and the display differ in size, `map_to_target` on the
+//! driver scales touch points into the bounding box of a `DrawTarget`.
+
+use crate::{MultiTouch, TouchState};
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::primitives::{ContainsPoint, Rectangle};
+
+impl From<TouchState> for Point {
+    fn from(touch: TouchState) -> Self {
+        touch.point()
+    }
+}
+
+impl TouchState {
+    /// Position of the touch as an embedded-graphics point
+    pub fn point(&self) -> Point {
+        Point::new(i32::from(self.x), i32::from(self.y))
+    }
+
+    /// Whether this is a detected touch inside `shape`
+    pub fn hits<S: ContainsPoint>(&self, shape: &S) -> bool {
+        self.detected && shape.contains(self.point())
+    }
+
+    /// Index of the first of `shapes` the touch is inside, e.g. the button
+    /// that was pressed
+    pub fn hit_index<S: ContainsPoint>(&self, shapes: &[S]) -> Option<usize> {
+        shapes.iter().position(|shape| self.hits(shape))
+    }
+}
+
+impl<const N: usize> MultiTouch<N> {
+    /// Whether any of the touch points is inside `shape`
+    pub fn hits<S: ContainsPoint>(&self, shape: &S) -> bool {
+        self.iter().any(|pt| pt.hits(shape))
+    }
+}
+
+/// Scale a touch point on a panel of `panel` size into `area`, e.g. the
+/// bounding box of a `DrawTarget`
+pub fn scale_to_area(touch: &TouchState, panel: Size, area: &Rectangle) -> Point {
+    let scale = |v: u16, from: u32, to: u32| {
+        let from = from.max(1);
+        let v = u32::from(v).min(from - 1);
+        (u64::from(v) * u64::from(to) / u64::from(from)) as i32
+    };
+    area.top_left
+        + Point::new(
+            scale(touch.x, panel.width, area.size.width),
+            scale(touch.y, panel.height, area.size.height),
+        )
+}
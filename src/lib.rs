@@ -1,43 +1,113 @@
 //!
-//! A platform agnostic driver for FT6X06 touchscreen . Built using 'embedded-hal' traits.
+//! A platform agnostic driver for FT6X06 touchscreen . Built using 'embedded-hal' 1.0 traits.
 //!
 //! The Touchscreen driver for FT6X06 series touch panel controller
 //!
+//! HALs that still implement embedded-hal 0.2 can be used through the
+//! adapters in the `eh02` module (enable the `eh02` feature).
+//! An async driver built on `embedded-hal-async` lives in the `asynch`
+//! module (enable the `async` feature).
 //!
 //!  ### Example
 //!
 //! ##### Initializing the Ft6x06 driver struct
-//! 		let mut touch = ft6x06::Ft6X06::new(i2c, addr, ts_int).unwrap();
+//! ```ignore
+//! let mut touch = ft6x06::Ft6X06::new(&i2c, addr, ts_int).unwrap();
+//! ```
+//!
+//! ##### Letting the driver own the I2C bus
+//! ```ignore
+//! let mut touch = ft6x06::Ft6X06Owned::new(i2c, addr, ts_int).unwrap();
+//! let n = touch.detect_touch().unwrap();
+//! let (i2c, ts_int) = touch.release();
+//! ```
 
 #![no_std]
 #![no_main]
 
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod calibration;
+mod config;
 pub mod constant;
+#[cfg(feature = "eh02")]
+pub mod eh02;
+mod error;
+pub mod filter;
+#[cfg(feature = "embedded-graphics")]
+pub mod graphics;
+mod owned;
+pub mod register;
+#[cfg(feature = "storage")]
+pub mod storage;
+mod transform;
+
+pub use crate::config::{DeviceConfig, GestureConfig, InitOptions, ScanConfig, ThresholdStats};
+pub use crate::error::Error;
+pub use crate::owned::Ft6X06Owned;
+pub use crate::transform::{Rotation, Transform};
 
 #[cfg(feature = "gesture")]
 use heapless::Vec;
 
+use crate::calibration::{Calibration, CalibrationError, Residual, Sample};
 use crate::constant::*;
+use crate::register::*;
+use crate::transform::Panel;
 use core::marker::PhantomData;
+#[cfg(feature = "embedded-graphics")]
+use embedded_graphics::geometry::{Dimensions, Point, Size};
 use embedded_hal as hal;
-use hal::blocking::{
-    delay::{DelayMs, DelayUs},
-    i2c,
-};
-use hal::digital::v2::OutputPin;
+#[cfg(feature = "storage")]
+use embedded_storage::nor_flash::ReadNorFlash;
+use hal::delay::DelayNs;
+use hal::digital::{Error as _, InputPin, OutputPin};
+use hal::i2c::I2c;
 
-#[derive(Copy, Clone, Debug)]
+/// What the controller and the panel it is mounted on can do
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Ft6x06Capabilities {
-    #[allow(dead_code)]
     multi_touch: bool,
-    #[allow(dead_code)]
     gesture: bool,
-    #[allow(dead_code)]
     max_touch: u8,
-    #[allow(dead_code)]
     max_x_length: u16,
-    #[allow(dead_code)]
-    may_y_length: u16,
+    max_y_length: u16,
+}
+
+impl Ft6x06Capabilities {
+    /// Whether more than one touch point is tracked
+    pub fn multi_touch(&self) -> bool {
+        self.multi_touch
+    }
+
+    /// Whether the controller reports gestures
+    pub fn gesture(&self) -> bool {
+        self.gesture
+    }
+
+    /// Number of touch points tracked
+    pub fn max_touch(&self) -> u8 {
+        self.max_touch
+    }
+
+    /// Panel width; reported x coordinates are below it
+    pub fn max_x_length(&self) -> u16 {
+        self.max_x_length
+    }
+
+    /// Panel height; reported y coordinates are below it
+    pub fn max_y_length(&self) -> u16 {
+        self.max_y_length
+    }
+
+    /// The same capabilities on a panel of the given size
+    pub(crate) fn with_panel(self, width: u16, height: u16) -> Self {
+        Ft6x06Capabilities {
+            max_x_length: width,
+            max_y_length: height,
+            ..self
+        }
+    }
 }
 
 const TRUE: bool = true;
@@ -48,10 +118,86 @@ const FT6X06_CAPABILITIES: Ft6x06Capabilities = Ft6x06Capabilities {
     //  Gesture is not set as per given in ft6x06 crate by STMicroelectronics
     gesture: FALSE,
     max_touch: FT6X06_MAX_NB_TOUCH as u8,
-    max_x_length: FT6X06_MAX_X_LENGTH,
-    may_y_length: FT6X06_MAX_Y_LENGTH,
+    max_x_length: FT6X06_MAX_COORDINATE + 1,
+    max_y_length: FT6X06_MAX_COORDINATE + 1,
 };
 
+const FT6X36_CAPABILITIES: Ft6x06Capabilities = Ft6x06Capabilities {
+    multi_touch: TRUE,
+    //  The FT6x36 family reports gestures in FT6X06_GEST_ID_REG
+    gesture: TRUE,
+    max_touch: FT6X06_MAX_NB_TOUCH as u8,
+    max_x_length: FT6X06_MAX_COORDINATE + 1,
+    max_y_length: FT6X06_MAX_COORDINATE + 1,
+};
+
+const FT5X06_CAPABILITIES: Ft6x06Capabilities = Ft6x06Capabilities {
+    multi_touch: TRUE,
+    gesture: TRUE,
+    max_touch: FT5X06_MAX_NB_TOUCH as u8,
+    max_x_length: FT6X06_MAX_COORDINATE + 1,
+    max_y_length: FT6X06_MAX_COORDINATE + 1,
+};
+
+const FT5336_CAPABILITIES: Ft6x06Capabilities = Ft6x06Capabilities {
+    multi_touch: TRUE,
+    gesture: TRUE,
+    max_touch: FT5336_MAX_NB_TOUCH as u8,
+    max_x_length: FT6X06_MAX_COORDINATE + 1,
+    max_y_length: FT6X06_MAX_COORDINATE + 1,
+};
+
+/// Controller variants the driver supports.
+///
+/// Once a variant is detected the driver adapts to it: on parts without a
+/// gesture engine (the FT6206) gesture reads report [`GestureKind::None`]
+/// without touching GEST_ID, and the gesture registers are left out of
+/// `read_config`/`apply_config`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ChipVariant {
+    /// FT6206, and FT6x06 parts with an unknown cipher
+    #[default]
+    Ft6206,
+    /// FT6236
+    Ft6236,
+    /// FT6336U
+    Ft6336,
+    /// FT6x36 parts reporting FT6X36_ID
+    Ft6x36,
+    /// FT5x06, up to FT5X06_MAX_NB_TOUCH points
+    Ft5x06,
+    /// FT5336, up to FT5336_MAX_NB_TOUCH points
+    Ft5336,
+}
+
+impl ChipVariant {
+    /// Identify the variant from FT6X06_CHIP_ID_REG and FT6X06_CIPHER_REG.
+    /// Parts reporting FT6X06_ID are told apart by their cipher.
+    pub(crate) fn detect(chip_id: u8, cipher: u8) -> Option<Self> {
+        match (chip_id, cipher) {
+            (FT6X36_ID, _) => Some(ChipVariant::Ft6x36),
+            (FT5336_ID, _) => Some(ChipVariant::Ft5336),
+            (FT6X06_ID, FT5X06_CIPHER) => Some(ChipVariant::Ft5x06),
+            (FT6X06_ID, FT6236_CIPHER) => Some(ChipVariant::Ft6236),
+            (FT6X06_ID, FT6336U_CIPHER) => Some(ChipVariant::Ft6336),
+            (FT6X06_ID, FT6206_CIPHER) => Some(ChipVariant::Ft6206),
+            // Unknown ciphers get the most conservative FT6x06 handling
+            (FT6X06_ID, _) => Some(ChipVariant::Ft6206),
+            _ => None,
+        }
+    }
+
+    /// Capabilities of this variant
+    pub fn capabilities(self) -> Ft6x06Capabilities {
+        match self {
+            ChipVariant::Ft6206 => FT6X06_CAPABILITIES,
+            ChipVariant::Ft6236 | ChipVariant::Ft6336 | ChipVariant::Ft6x36 => FT6X36_CAPABILITIES,
+            ChipVariant::Ft5x06 => FT5X06_CAPABILITIES,
+            ChipVariant::Ft5336 => FT5336_CAPABILITIES,
+        }
+    }
+}
+
 /// Touch structure - derived from the available I2C registers.
 // #define FT6X06_P1_XH_REG            0x03U
 // #define FT6X06_P1_XL_REG            0x04U
@@ -62,7 +208,7 @@ const FT6X06_CAPABILITIES: Ft6x06Capabilities = Ft6x06Capabilities {
 //   followed by:
 // #define FT6X06_P2_XH_REG            0x09U
 // etc
-#[derive(Copy, Clone, Debug, PartialOrd, Ord, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, PartialOrd, Ord, Eq, PartialEq)]
 pub struct TouchState {
     /// Was a touch detected:
     pub detected: bool,
@@ -74,22 +220,175 @@ pub struct TouchState {
     pub weight: u8,
     /// Misc (contents not known)
     pub misc: u8,
+    /// Event flag of the point, from the top bits of Pn_XH
+    pub event: TouchEvent,
+    /// ID of the finger, from the top bits of Pn_YH.
+    /// It follows the finger when the points change slots.
+    pub touch_id: u8,
 }
 
-/// For storing multi-touch data
-pub struct MultiTouch {
-    pub detected: bool,
-    /// X postion
-    pub touch_x: [u16; 2],
-    /// Y position
-    pub touch_y: [u16; 2],
-    /// Weight of touch
-    pub touch_weight: [u16; 2],
-    /// Misc (contents not known)
-    pub touch_area: [u16; 2],
+impl TouchState {
+    /// Decode one point from its six registers, Pn_XH through Pn_MISC
+    pub(crate) fn from_registers(buf: &[u8]) -> Self {
+        // Tried copying the c code literally here. It makes no difference though
+        let x: u16 = (FT6X06_P1_XH_TP_BIT_MASK & buf[0]) as u16 * 256 + buf[1] as u16;
+        let y: u16 = (FT6X06_P1_YH_TP_BIT_MASK & buf[2]) as u16 * 256 + buf[3] as u16;
+
+        TouchState {
+            detected: true,
+            x,
+            y,
+            weight: buf[4],
+            misc: buf[5],
+            event: TouchEvent::from_flag(
+                (buf[0] & FT6X06_TOUCH_EVT_FLAG_MASK) >> FT6X06_TOUCH_EVT_FLAG_SHIFT,
+            ),
+            touch_id: (buf[2] & FT6X06_TOUCH_ID_MASK) >> FT6X06_TOUCH_ID_SHIFT,
+        }
+    }
+
+    /// Map the raw coordinates onto the display
+    pub(crate) fn to_panel(self, panel: &Panel) -> Self {
+        let (x, y) = panel.map(self.x, self.y);
+        TouchState { x, y, ..self }
+    }
+
+    /// Touch area, from the top bits of Pn_MISC
+    pub fn area(&self) -> u8 {
+        (self.misc & FT6X06_TOUCH_AREA_MASK) >> FT6X06_TOUCH_AREA_SHIFT
+    }
+}
+
+/// Event flag reported with each touch point
+#[derive(Copy, Clone, Debug, Default, PartialOrd, Ord, Eq, PartialEq)]
+pub enum TouchEvent {
+    /// The finger just touched the panel
+    PressDown,
+    /// The finger just left the panel
+    LiftUp,
+    /// The finger stays on the panel
+    Contact,
+    /// Nothing happened on this point
+    #[default]
+    NoEvent,
+}
+
+impl TouchEvent {
+    /// Decode the 2-bit event flag of Pn_XH
+    pub(crate) fn from_flag(flag: u8) -> Self {
+        match flag {
+            FT6X06_TOUCH_EVT_FLAG_PRESS_DOWN => TouchEvent::PressDown,
+            FT6X06_TOUCH_EVT_FLAG_LIFT_UP => TouchEvent::LiftUp,
+            FT6X06_TOUCH_EVT_FLAG_CONTACT => TouchEvent::Contact,
+            _ => TouchEvent::NoEvent,
+        }
+    }
+}
+
+/// Touch point slot of a controller tracking `N` points: P1 (registers
+/// 0x03..0x08), P2 (registers 0x09..0x0E) and so on. The FT6x06 has P1 and
+/// P2, the FT5x06 up to P5 and the FT5336 up to P10.
+///
+/// Naming a slot past `N`, e.g. `TouchSlot::<2>::P5`, fails to compile.
+#[derive(Copy, Clone, Debug, PartialOrd, Ord, Eq, PartialEq)]
+pub struct TouchSlot<const N: usize = FT6X06_MAX_NB_TOUCH>(u8);
+
+impl<const N: usize> TouchSlot<N> {
+    /// First touch point
+    pub const P1: Self = Self::nth(0);
+    /// Second touch point
+    pub const P2: Self = Self::nth(1);
+    /// Third touch point
+    pub const P3: Self = Self::nth(2);
+    /// Fourth touch point
+    pub const P4: Self = Self::nth(3);
+    /// Fifth touch point
+    pub const P5: Self = Self::nth(4);
+    /// Sixth touch point
+    pub const P6: Self = Self::nth(5);
+    /// Seventh touch point
+    pub const P7: Self = Self::nth(6);
+    /// Eighth touch point
+    pub const P8: Self = Self::nth(7);
+    /// Ninth touch point
+    pub const P9: Self = Self::nth(8);
+    /// Tenth touch point
+    pub const P10: Self = Self::nth(9);
+
+    const fn nth(index: usize) -> Self {
+        assert!(index < N, "the driver tracks fewer touch points");
+        TouchSlot(index as u8)
+    }
+
+    /// Slot of the zero-based point `index`, `None` from `N` on
+    pub fn from_index(index: usize) -> Option<Self> {
+        (index < N).then_some(TouchSlot(index as u8))
+    }
+
+    /// Zero-based index of the slot, 0 for P1
+    pub fn index(self) -> usize {
+        usize::from(self.0)
+    }
+
+    /// Address of the Pn_XH register, the first register of the slot
+    pub fn xh_reg(self) -> u8 {
+        FT6X06_P1_XH_REG + (self.index() * FT6X06_POINT_LEN) as u8
+    }
+}
+
+/// The touch points present in one scan, at most `N`, the number of points
+/// the controller tracks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MultiTouch<const N: usize = FT6X06_MAX_NB_TOUCH> {
+    points: [TouchState; N],
+    len: usize,
+}
+
+impl<const N: usize> MultiTouch<N> {
+    /// Collect the given points, at most `N` of them
+    pub(crate) fn from_points(points: &[TouchState]) -> Self {
+        let mut multi = MultiTouch {
+            points: [TouchState::default(); N],
+            len: 0,
+        };
+        for pt in points.iter().take(N) {
+            multi.points[multi.len] = *pt;
+            multi.len += 1;
+        }
+        multi
+    }
+
+    /// Number of touch points
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether nobody touches the screen
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The touch points as a slice
+    pub fn points(&self) -> &[TouchState] {
+        &self.points[..self.len]
+    }
+
+    /// Iterate over the touch points
+    pub fn iter(&self) -> core::slice::Iter<'_, TouchState> {
+        self.points().iter()
+    }
 }
 
-#[derive(Debug)]
+impl<'a, const N: usize> IntoIterator for &'a MultiTouch<N> {
+    type Item = &'a TouchState;
+    type IntoIter = core::slice::Iter<'a, TouchState>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 /// Possible choices of gesture
 pub enum GestureKind {
     /// No gesture detected
@@ -110,6 +409,160 @@ pub enum GestureKind {
     Fault,
 }
 
+impl GestureKind {
+    /// Decode the value of FT6X06_GEST_ID_REG
+    pub(crate) fn from_gest_id(id: u8) -> Self {
+        match id {
+            FT6X06_GEST_ID_NO_GESTURE => GestureKind::None,
+            FT6X06_GEST_ID_MOVE_UP => GestureKind::Up,
+            FT6X06_GEST_ID_MOVE_RIGHT => GestureKind::Right,
+            FT6X06_GEST_ID_MOVE_DOWN => GestureKind::Down,
+            FT6X06_GEST_ID_MOVE_LEFT => GestureKind::Left,
+            FT6X06_GEST_ID_ZOOM_IN => GestureKind::ZoomIn,
+            FT6X06_GEST_ID_ZOOM_OUT => GestureKind::ZoomOut,
+            _ => GestureKind::Fault,
+        }
+    }
+}
+
+/// Power mode of the controller
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PowerMode {
+    /// Scanning at the active report rate
+    Active,
+    /// Scanning at the monitor report rate until a touch is seen
+    Monitor,
+    /// Not scanning and not answering on I2C until reset
+    Hibernate,
+}
+
+impl PowerMode {
+    /// Decode the value of FT6X06_PWR_MODE_REG
+    pub(crate) fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            FT6X06_PWR_MODE_ACTIVE => Some(PowerMode::Active),
+            FT6X06_PWR_MODE_MONITOR => Some(PowerMode::Monitor),
+            FT6X06_PWR_MODE_HIBERNATE => Some(PowerMode::Hibernate),
+            _ => None,
+        }
+    }
+
+    /// Value of FT6X06_PWR_MODE_REG for this mode
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            PowerMode::Active => FT6X06_PWR_MODE_ACTIVE,
+            PowerMode::Monitor => FT6X06_PWR_MODE_MONITOR,
+            PowerMode::Hibernate => FT6X06_PWR_MODE_HIBERNATE,
+        }
+    }
+}
+
+/// How the controller drives the INT pin
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum InterruptMode {
+    /// INT stays low for as long as a touch is present
+    #[default]
+    Polling,
+    /// INT pulses low once for every report
+    Trigger,
+}
+
+impl InterruptMode {
+    /// Decode the interrupt field of FT6X06_GMODE_REG
+    pub(crate) fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            FT6X06_G_MODE_INTERRUPT_POLLING => Some(InterruptMode::Polling),
+            FT6X06_G_MODE_INTERRUPT_TRIGGER => Some(InterruptMode::Trigger),
+            _ => None,
+        }
+    }
+
+    /// Value of the interrupt field of FT6X06_GMODE_REG for this mode
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            InterruptMode::Polling => FT6X06_G_MODE_INTERRUPT_POLLING,
+            InterruptMode::Trigger => FT6X06_G_MODE_INTERRUPT_TRIGGER,
+        }
+    }
+}
+
+/// Identification and version registers of the controller
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// Chip identification
+    pub chip_id: u8,
+    /// Firmware version
+    pub firmware_id: u8,
+    /// Library version, high byte first
+    pub lib_version: u16,
+    /// Chip vendor ID
+    pub cipher: u8,
+    /// Release code version
+    pub release_code_id: u8,
+}
+
+impl DeviceInfo {
+    /// Decode the registers FT6X06_LIB_VER_H_REG through FT6X06_RELEASE_CODE_ID_REG
+    pub(crate) fn from_registers(buf: &[u8; FT6X06_INFO_LEN]) -> Self {
+        let at = |reg: u8| buf[(reg - FT6X06_LIB_VER_H_REG) as usize];
+        DeviceInfo {
+            chip_id: at(FT6X06_CHIP_ID_REG),
+            firmware_id: at(FT6X06_FIRMID_REG),
+            lib_version: u16::from_be_bytes([at(FT6X06_LIB_VER_H_REG), at(FT6X06_LIB_VER_L_REG)]),
+            cipher: at(FT6X06_CIPHER_REG),
+            release_code_id: at(FT6X06_RELEASE_CODE_ID_REG),
+        }
+    }
+}
+
+/// Everything the controller reports for one scan, read in a single transfer
+/// so the gesture, the touch count and the points belong together.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TouchReport<const N: usize = FT6X06_MAX_NB_TOUCH> {
+    /// Gesture reported with this scan
+    pub gesture: GestureKind,
+    /// Number of touch points reported in TD_STATUS
+    pub touch_count: u8,
+    points: [TouchState; N],
+}
+
+impl<const N: usize> TouchReport<N> {
+    /// Length of the report: FT6X06_DEV_MODE_REG through the last register
+    /// of point `N`
+    pub(crate) const LEN: usize = FT6X06_P1_XH_REG as usize + N * FT6X06_POINT_LEN;
+
+    /// Decode the registers FT6X06_DEV_MODE_REG through the MISC register of
+    /// point `N`. Fails with the touch count if TD_STATUS reports too many touches.
+    pub(crate) fn from_registers(buf: &[u8]) -> Result<Self, u8> {
+        let touch_count = TdStatus::from_bits(buf[FT6X06_TD_STAT_REG as usize]).touch_points();
+        if touch_count as usize > N {
+            return Err(touch_count);
+        }
+
+        Ok(TouchReport {
+            gesture: GestId::from_bits(buf[FT6X06_GEST_ID_REG as usize]).gesture(),
+            touch_count,
+            points: core::array::from_fn(|n| {
+                let start = FT6X06_P1_XH_REG as usize + n * FT6X06_POINT_LEN;
+                TouchState::from_registers(&buf[start..start + FT6X06_POINT_LEN])
+            }),
+        })
+    }
+
+    /// The touch points present in this scan
+    pub fn points(&self) -> &[TouchState] {
+        &self.points[..self.touch_count as usize]
+    }
+
+    /// Map the raw coordinates of all points onto the display
+    pub(crate) fn to_panel(mut self, panel: &Panel) -> Self {
+        for pt in self.points.iter_mut() {
+            *pt = pt.to_panel(panel);
+        }
+        self
+    }
+}
+
 // Gestures don't seem to work using values of control registers and reading radian_value_reg.
 // I tried working with the GestureInit struct and i2c bus to read gestures but failed.
 // I removed its impl but kept the struct to give idea of how it is implementated in C.
@@ -149,287 +602,818 @@ pub struct GestureInit<I2C> {
 
 /// FT6x06 driver object.
 /// I2C bus type and its address are set.
-pub struct Ft6X06<I2C, TouchInterruptPin> {
+/// The bus itself is passed to every call; see [`Ft6X06Owned`] for a driver
+/// that owns it.
+///
+/// `N` is the number of touch points the controller tracks. The FT5x06
+/// family shares the register layout and only tracks more points, see
+/// [`Ft5X06`] and [`Ft5336`].
+pub struct Ft6X06<I2C, TouchInterruptPin, const N: usize = FT6X06_MAX_NB_TOUCH> {
     i2c: PhantomData<I2C>,
     addr: u8,
     interrupt: TouchInterruptPin,
+    interrupt_mode: InterruptMode,
+    variant: Option<ChipVariant>,
+    panel: Panel,
 }
 
+/// Check a panel size against what the controller can report
+pub(crate) fn check_panel_size<E, PinE>(width: u16, height: u16) -> Result<(), Error<E, PinE>> {
+    let valid = 1..=FT6X06_MAX_COORDINATE + 1;
+    if !valid.contains(&width) || !valid.contains(&height) {
+        return Err(Error::InvalidPanelSize { width, height });
+    }
+    Ok(())
+}
+
+/// Driver for FT5x06 controllers, tracking up to FT5X06_MAX_NB_TOUCH points
+pub type Ft5X06<I2C, TouchInterruptPin> = Ft6X06<I2C, TouchInterruptPin, FT5X06_MAX_NB_TOUCH>;
+
+/// Driver for FT5336 controllers, tracking up to FT5336_MAX_NB_TOUCH points
+pub type Ft5336<I2C, TouchInterruptPin> = Ft6X06<I2C, TouchInterruptPin, FT5336_MAX_NB_TOUCH>;
+
 /// Perform a long hard reset, the FT66206 needs at least 5mS ...
 //
 // - On the STM32F413 the touchscreen shares the reset GPIO pin w/ the LCD.
 // - The ST7789 driver uses a fast (10uS) reset.
 // - The touchscreen controller needs 5mS:
 //   https://www.displayfuture.com/Display/datasheet/controller/FT6206.pdf
-pub fn long_hard_reset<'a, RST, DELAY>(
-    rst: &'a mut RST,
-    delay: &'a mut DELAY,
-) -> Result<(), &'a str>
+pub fn long_hard_reset<RST, DELAY>(rst: &mut RST, delay: &mut DELAY) -> Result<(), RST::Error>
 where
     RST: OutputPin,
-    DELAY: DelayUs<u32>,
+    DELAY: DelayNs,
 {
-    rst.set_low().map_err(|_| "rst.set_low failed")?;
+    rst.set_low()?;
     delay.delay_us(10_000);
-    rst.set_high().map_err(|_| "rst.set_high failed")?;
+    rst.set_high()?;
 
     Ok(())
 }
 
-impl<I2C, TouchInterruptPin: hal::digital::v2::InputPin, E> Ft6X06<I2C, TouchInterruptPin>
+impl<I2C, TouchInterruptPin, E, PinE> Ft6X06<I2C, TouchInterruptPin>
 where
-    I2C: i2c::WriteRead<Error = E> + i2c::Write<Error = E>,
-    E: core::fmt::Debug,
+    I2C: I2c<Error = E>,
+    TouchInterruptPin: InputPin<Error = PinE>,
 {
     /// Creates a new sensor associated with an I2C peripheral.
     ///
     /// Phantom I2C ensures that whatever I2C bus the device was created on is the one that is used for all future interations.
-    pub fn new(_i2c: &I2C, addr: u8, interrupt: TouchInterruptPin) -> Result<Self, E> {
+    pub fn new(i2c: &I2C, addr: u8, interrupt: TouchInterruptPin) -> Result<Self, Error<E, PinE>> {
+        Self::with_max_touch(i2c, addr, interrupt)
+    }
+}
+
+impl<I2C, TouchInterruptPin, E, PinE, const N: usize> Ft6X06<I2C, TouchInterruptPin, N>
+where
+    I2C: I2c<Error = E>,
+    TouchInterruptPin: InputPin<Error = PinE>,
+{
+    /// Creates a new sensor tracking up to `N` touch points, e.g.
+    /// `Ft5336::with_max_touch(&i2c, 0x38, ts_int)`.
+    pub fn with_max_touch(
+        _i2c: &I2C,
+        addr: u8,
+        interrupt: TouchInterruptPin,
+    ) -> Result<Self, Error<E, PinE>> {
+        const { assert!(N >= 1 && N <= FT5336_MAX_NB_TOUCH) };
         let ft6x06 = Ft6X06 {
             i2c: PhantomData,
-            addr: addr,
+            addr,
             interrupt,
+            interrupt_mode: InterruptMode::default(),
+            variant: None,
+            panel: Panel::DEFAULT,
         };
         Ok(ft6x06)
     }
 
+    /// Transform applied to every touch point
+    pub fn transform(&self) -> Transform {
+        self.panel.transform
+    }
+
+    /// Change the transform applied to every touch point, e.g. when the UI
+    /// rotates. Mirroring and rotation flip coordinates against the panel
+    /// size, so set it with [`with_panel_size`](Self::with_panel_size) first.
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.panel.transform = transform;
+    }
+
+    /// Calibration applied to every touch point
+    pub fn calibration(&self) -> Option<Calibration> {
+        self.panel.calibration
+    }
+
+    /// Change the calibration applied to every touch point
+    pub fn set_calibration(&mut self, calibration: Option<Calibration>) {
+        self.panel.calibration = calibration;
+    }
+
+    /// Fit a calibration to samples read with
+    /// [`get_raw_touch`](Self::get_raw_touch) whose targets are display
+    /// positions under the current transform, see [`calibration`]
+    pub fn fit_calibration(
+        &self,
+        samples: &[Sample],
+    ) -> Result<(Calibration, Residual), CalibrationError> {
+        self.panel.fit_calibration(samples)
+    }
+
+    /// Scale a touch point from the panel into the bounding box of
+    /// `target`, e.g. when the panel and the display differ in resolution
+    #[cfg(feature = "embedded-graphics")]
+    pub fn map_to_target<D: Dimensions>(&self, touch: &TouchState, target: &D) -> Point {
+        let (width, height) = self.panel.output_size();
+        graphics::scale_to_area(
+            touch,
+            Size::new(u32::from(width), u32::from(height)),
+            &target.bounding_box(),
+        )
+    }
+
+    /// Set the size of the panel the controller is mounted on, e.g.
+    /// `Ft6X06::new(&i2c, 0x38, ts_int)?.with_panel_size(240, 240)?`.
+    /// Reported coordinates are clamped to it. Without a panel size the
+    /// driver passes the full 12 bit coordinate range through unclamped.
+    pub fn with_panel_size(mut self, width: u16, height: u16) -> Result<Self, Error<E, PinE>> {
+        check_panel_size(width, height)?;
+        self.panel.width = width;
+        self.panel.height = height;
+        Ok(self)
+    }
+
     /// Initialise device and disable interupt mode.
     /// FT6X06 should be calibrated once after each power up.
-    pub fn init(&mut self, i2c: &mut I2C, delay_source: &mut impl DelayMs<u32>) {
-        // -> Result<Self, E> {
-        if FT6X06_AUTO_CALIBRATION_ENABLED {
-            self.ts_calibration(i2c, delay_source).unwrap();
+    /// Same as [`init_with`](Self::init_with) with the default [`InitOptions`]
+    /// and [`InterruptMode::Polling`].
+    pub fn init(
+        &mut self,
+        i2c: &mut I2C,
+        delay_source: &mut impl DelayNs,
+    ) -> Result<(), Error<E, PinE>> {
+        let options = InitOptions::new().interrupt_mode(InterruptMode::Polling);
+        self.init_with(i2c, delay_source, &options)?;
+        Ok(())
+    }
+
+    /// Bring the controller up after power-on: wait out the start-up time,
+    /// check the chip ID and detect the variant, then set it up as
+    /// `options` say. Returns the detected variant.
+    pub fn init_with(
+        &mut self,
+        i2c: &mut I2C,
+        delay_source: &mut impl DelayNs,
+        options: &InitOptions,
+    ) -> Result<ChipVariant, Error<E, PinE>> {
+        delay_source.delay_ms(FT6X06_STARTUP_TIME_MS);
+        let variant = self.detect_variant(i2c)?;
+        if let Some(config) = &options.config {
+            self.apply_config(i2c, config)?;
         }
-        // FT6X06_DisableIT(i2c)?;
-        // Ok(*self)
+        if let Some(mode) = options.interrupt_mode {
+            self.set_interrupt_mode(i2c, mode)?;
+        } else if options.config.is_none() {
+            // G_MODE stays as it is, but wait_touch_interrupt needs to know it
+            self.interrupt_mode(i2c)?;
+        }
+        if options.calibrate {
+            self.ts_calibration(i2c, delay_source)?;
+        }
+        Ok(variant)
+    }
+
+    /// Like [`init_with`](Self::init_with), after a long hard reset through `rst`
+    pub fn reset_and_init<RST: OutputPin>(
+        &mut self,
+        i2c: &mut I2C,
+        rst: &mut RST,
+        delay_source: &mut impl DelayNs,
+        options: &InitOptions,
+    ) -> Result<ChipVariant, Error<E, PinE>> {
+        long_hard_reset(rst, delay_source).map_err(|e| Error::ResetPin(e.kind()))?;
+        self.init_with(i2c, delay_source, options)
+    }
+
+    /// Like [`init_with`](Self::init_with), with the calibration and config
+    /// of the settings record at `offset` in `flash`, see [`storage`].
+    /// An interrupt mode set in `options` overrides the stored one.
+    /// Without a valid record, `options` apply as given and the calibration
+    /// is cleared. Returns the detected variant and whether the record was
+    /// used.
+    #[cfg(feature = "storage")]
+    pub fn init_from_storage<S: ReadNorFlash>(
+        &mut self,
+        i2c: &mut I2C,
+        delay_source: &mut impl DelayNs,
+        flash: &mut S,
+        offset: u32,
+        options: &InitOptions,
+    ) -> Result<(ChipVariant, bool), Error<E, PinE>> {
+        let (options, calibration, restored) = storage::restore(flash, offset, options);
+        self.set_calibration(calibration);
+        let variant = self.init_with(i2c, delay_source, &options)?;
+        Ok((variant, restored))
     }
 
     ///As the ft6X06 library owns the delay, the simplest way to
     /// deliver it to the callign code seems to be to return a function call.
-    pub fn delay_ms(&mut self, delay_source: &mut impl DelayMs<u32>, delay: u32) {
+    pub fn delay_ms(&mut self, delay_source: &mut impl DelayNs, delay: u32) {
         delay_source.delay_ms(delay);
     }
 
     /// Returns the structure that contains all the preset capabilities
-    /// of the FT6X06
+    /// of the detected variant, see [`detect_variant`](Self::detect_variant)
     pub fn get_capabilities(&self) -> Ft6x06Capabilities {
-        FT6X06_CAPABILITIES
+        let (width, height) = self.panel.output_size();
+        self.variant().capabilities().with_panel(width, height)
     }
 
-    /// Read whether the FT5663 is in dev mode or not
-    pub fn dev_mode_r(&self, i2c: &mut I2C) -> Result<u8, E> {
-        let mut buf: [u8; 1] = [0];
-
-        i2c.write_read(self.addr, &[FT6X06_DEV_MODE_REG], &mut buf)?;
-
-        let mut value = buf[0];
-        value &= FT6X06_DEV_MODE_BIT_MASK;
-        value &= FT6X06_DEV_MODE_BIT_POSITION;
-
-        Ok(value)
+    /// Variant found by the last [`detect_variant`](Self::detect_variant),
+    /// [`ChipVariant::Ft6206`] before that
+    pub fn variant(&self) -> ChipVariant {
+        self.variant.unwrap_or_default()
     }
 
-    /// Put the FT5663 into dev mode
-    pub fn dev_mode_w(&self, i2c: &mut I2C, value: u8) -> Result<bool, E> {
-        let mut buf: [u8; 1] = [0];
+    /// Whether the controller has a gesture engine. Until the variant is
+    /// detected it is assumed to have one.
+    fn has_gestures(&self) -> bool {
+        self.variant.is_none_or(|v| v.capabilities().gesture())
+    }
 
-        i2c.write_read(self.addr, &[FT6X06_DEV_MODE_REG], &mut buf)?;
+    /// Number of slots holding touch data: `N`, or fewer once a variant
+    /// that tracks fewer points is detected
+    fn slot_count(&self) -> usize {
+        self.variant
+            .map_or(N, |v| N.min(v.capabilities().max_touch() as usize))
+    }
 
-        let mut tmp = buf[0];
+    /// Identify the controller variant and use its capabilities from then on.
+    /// Fails with [`Error::TooManyTouchPoints`] if the variant tracks more
+    /// than `N` touch points.
+    pub fn detect_variant(&mut self, i2c: &mut I2C) -> Result<ChipVariant, Error<E, PinE>> {
+        let id = self.read_register::<ChipId>(i2c)?.value();
+        let cipher = self.read_register::<Cipher>(i2c)?.value();
+        let variant = ChipVariant::detect(id, cipher).ok_or(Error::WrongChipId(id))?;
+        if variant.capabilities().max_touch() as usize > N {
+            return Err(Error::TooManyTouchPoints(variant));
+        }
+        self.variant = Some(variant);
+        Ok(variant)
+    }
 
-        tmp &= !FT6X06_DEV_MODE_BIT_MASK;
-        tmp |= value << FT6X06_DEV_MODE_BIT_POSITION;
+    /// Read whether the FT5663 is in dev mode or not
+    pub fn dev_mode_r(&self, i2c: &mut I2C) -> Result<u8, Error<E, PinE>> {
+        let dev_mode: DevMode = self.read_register(i2c)?;
+        Ok(dev_mode.mode())
+    }
 
-        i2c.write(self.addr, &[tmp])?;
+    /// Put the FT5663 into dev mode
+    pub fn dev_mode_w(&self, i2c: &mut I2C, value: u8) -> Result<bool, Error<E, PinE>> {
+        // No read-back: calibration moves the controller out of factory mode on its own.
+        self.modify_register::<DevMode>(i2c, |r| r.set_mode(value), false)?;
 
         Ok(value == 0)
     }
 
     /// Get the value of an 8 bit register
-    pub fn get_u8_reg(&self, i2c: &mut I2C, reg: u8) -> Result<u8, E> {
+    pub fn get_u8_reg(&self, i2c: &mut I2C, reg: u8) -> Result<u8, Error<E, PinE>> {
         let mut ibuf: [u8; 1] = [0];
-        i2c.write_read(self.addr, &[reg], &mut ibuf)?;
+        self.read_regs(i2c, reg, &mut ibuf)?;
         Ok(ibuf[0])
     }
 
     /// Set the value of an 8 bit register
-    pub fn set_u8_reg(&self, i2c: &mut I2C, reg: u8, val: u8) -> Result<(), E> {
+    pub fn set_u8_reg(&self, i2c: &mut I2C, reg: u8, val: u8) -> Result<(), Error<E, PinE>> {
         let obuf: [u8; 2] = [reg, val];
-        i2c.write(self.addr, &obuf)?;
+        i2c.write(self.addr, &obuf).map_err(Error::Bus)
+    }
+
+    /// Read a typed register
+    pub fn read_register<R: Register>(&self, i2c: &mut I2C) -> Result<R, Error<E, PinE>> {
+        Ok(R::from_bits(self.get_u8_reg(i2c, R::ADDR)?))
+    }
+
+    /// Write a typed register
+    pub fn write_register<R: Writable>(&self, i2c: &mut I2C, reg: R) -> Result<(), Error<E, PinE>> {
+        self.set_u8_reg(i2c, R::ADDR, reg.bits())
+    }
+
+    /// Read-modify-write a typed register: `f` sets fields of the value read,
+    /// the other fields are kept. With `verify` the register is read back and
+    /// [`Error::ReadBack`] is returned if it did not take the value.
+    /// Returns the value written.
+    pub fn modify_register<R: Writable>(
+        &self,
+        i2c: &mut I2C,
+        f: impl FnOnce(&mut R),
+        verify: bool,
+    ) -> Result<R, Error<E, PinE>> {
+        let mut reg: R = self.read_register(i2c)?;
+        f(&mut reg);
+        self.write_register(i2c, reg)?;
+        if verify {
+            let read = self.get_u8_reg(i2c, R::ADDR)?;
+            if read != reg.bits() {
+                return Err(Error::ReadBack {
+                    reg: R::ADDR,
+                    wrote: reg.bits(),
+                    read,
+                });
+            }
+        }
+        Ok(reg)
+    }
+
+    /// Read-modify-write a register: the bits under `mask` are replaced by
+    /// those of `value`, the others are kept. Returns the value written.
+    pub fn modify_reg(
+        &self,
+        i2c: &mut I2C,
+        reg: u8,
+        mask: u8,
+        value: u8,
+    ) -> Result<u8, Error<E, PinE>> {
+        let old = self.get_u8_reg(i2c, reg)?;
+        let new = (old & !mask) | (value & mask);
+        self.set_u8_reg(i2c, reg, new)?;
+        Ok(new)
+    }
+
+    /// Write the field `mask`/`shift` of a register, leaving the other bits as
+    /// they are. With `verify` the register is read back and
+    /// [`Error::ReadBack`] is returned if the field did not take the value.
+    pub fn write_field(
+        &self,
+        i2c: &mut I2C,
+        reg: u8,
+        mask: u8,
+        shift: u8,
+        value: u8,
+        verify: bool,
+    ) -> Result<(), Error<E, PinE>> {
+        let wrote = self.modify_reg(i2c, reg, mask, value << shift)?;
+        if verify {
+            let read = self.get_u8_reg(i2c, reg)?;
+            if read & mask != wrote & mask {
+                return Err(Error::ReadBack { reg, wrote, read });
+            }
+        }
         Ok(())
     }
 
-    /// Wait for the touchscreen interrupt to indicate touches
-    pub fn wait_touch_interrupt(&self) {
-        while self
-            .interrupt
-            .is_high()
-            .unwrap_or_else(|_| panic!("trouble checking interrupt"))
-        {}
+    /// Read consecutive registers starting at `reg` in one transfer
+    fn read_regs(&self, i2c: &mut I2C, reg: u8, buf: &mut [u8]) -> Result<(), Error<E, PinE>> {
+        i2c.write_read(self.addr, &[reg], buf).map_err(Error::Bus)
+    }
+
+    /// Read the interrupt mode from the controller. The driver also uses the
+    /// result for [`wait_touch_interrupt`](Self::wait_touch_interrupt) from then on.
+    pub fn interrupt_mode(&mut self, i2c: &mut I2C) -> Result<InterruptMode, Error<E, PinE>> {
+        let g_mode: GMode = self.read_register(i2c)?;
+        let mode = g_mode.interrupt_mode().ok_or(Error::UnexpectedValue {
+            reg: GMode::ADDR,
+            value: g_mode.bits(),
+        })?;
+        self.interrupt_mode = mode;
+        Ok(mode)
+    }
+
+    /// Select how the controller drives the INT pin
+    pub fn set_interrupt_mode(
+        &mut self,
+        i2c: &mut I2C,
+        mode: InterruptMode,
+    ) -> Result<(), Error<E, PinE>> {
+        self.modify_register::<GMode>(i2c, |r| r.set_interrupt_mode(mode), true)?;
+        self.interrupt_mode = mode;
+        Ok(())
+    }
+
+    /// Wait for the touchscreen interrupt to indicate touches.
+    ///
+    /// In [`InterruptMode::Polling`] this returns as soon as INT is low, i.e.
+    /// at once while a touch is held. In [`InterruptMode::Trigger`] a low INT
+    /// may belong to a report that was already read, so this waits for the
+    /// next falling edge.
+    pub fn wait_touch_interrupt(&mut self) -> Result<(), Error<E, PinE>> {
+        if self.interrupt_mode == InterruptMode::Trigger {
+            while self.interrupt.is_low().map_err(Error::Pin)? {}
+        }
+        while self.interrupt.is_high().map_err(Error::Pin)? {}
+        Ok(())
+    }
+
+    /// Like [`wait_touch_interrupt`](Self::wait_touch_interrupt), but gives up
+    /// with [`Error::Timeout`] when the interrupt does not come within `timeout_ms`.
+    /// The pin is sampled every FT6X06_POLL_INTERVAL_MS, so trigger pulses
+    /// shorter than that can be missed.
+    pub fn wait_touch_interrupt_timeout(
+        &mut self,
+        timeout_ms: u32,
+        delay_source: &mut impl DelayNs,
+    ) -> Result<(), Error<E, PinE>> {
+        let mut waited_ms = 0;
+        let mut armed = self.interrupt_mode == InterruptMode::Polling;
+        loop {
+            let low = self.interrupt.is_low().map_err(Error::Pin)?;
+            if low && armed {
+                return Ok(());
+            }
+            armed |= !low;
+            if waited_ms >= timeout_ms {
+                return Err(Error::Timeout);
+            }
+            delay_source.delay_ms(FT6X06_POLL_INTERVAL_MS);
+            waited_ms += FT6X06_POLL_INTERVAL_MS;
+        }
     }
 
     /// Run an internal calibration on the FT6X06
     pub fn ts_calibration(
         &mut self,
         i2c: &mut I2C,
-        delay_source: &mut impl DelayMs<u32>,
-    ) -> Result<bool, &str> {
-        //} -> Result<Self, E> {
-        let mut _ret = FT6X06_OK;
-        let mut _nbr_attempt: u32;
-        let mut _read_data: u8;
-        let mut _end_calibration: u8;
-
-        let _result = self.dev_mode_w(i2c, FT6X06_DEV_MODE_FACTORY);
+        delay_source: &mut impl DelayNs,
+    ) -> Result<bool, Error<E, PinE>> {
+        self.dev_mode_w(i2c, FT6X06_DEV_MODE_FACTORY)?;
 
         delay_source.delay_ms(300);
 
         for _attempt in 0..100 {
-            match self.dev_mode_r(i2c) {
-                Err(_e) => return Err("Bad comms in ts_calibration"),
-                Ok(n) => {
-                    if n == FT6X06_DEV_MODE_WORKING {
-                        return Ok(true);
-                    }
-                }
+            if self.dev_mode_r(i2c)? == FT6X06_DEV_MODE_WORKING {
+                return Ok(true);
             }
             delay_source.delay_ms(200);
         }
-        Err("Calibration does not return")
+        Err(Error::CalibrationTimeout)
     }
 
     /// Read the touch device status
-    pub fn td_status(&self, i2c: &mut I2C) -> Result<u8, E> {
-        let mut buf: [u8; 1] = [0];
-        i2c.write_read(self.addr, &[FT6X06_TD_STAT_REG], &mut buf)?;
-        Ok(buf[0])
+    pub fn td_status(&self, i2c: &mut I2C) -> Result<u8, Error<E, PinE>> {
+        let td_status: TdStatus = self.read_register(i2c)?;
+        Ok(td_status.touch_points())
     }
 
     /// Read the touch device chip ID. It should be 0x51 if it is the FT6X06 on the
-    /// stm32f746 Discovery board
-    pub fn chip_id(&self, i2c: &mut I2C) -> Result<u8, &str> {
-        let mut buf: [u8; 1] = [0];
-        match i2c.write_read(self.addr, &[FT6X06_CHIP_ID_REG], &mut buf) {
-            Err(_e) => Err("Chip ID call failed"),
-            Ok(_a) => {
-                if buf[0] != FT6X06_ID {
-                    Err("error in chip ID")
-                } else {
-                    Ok(buf[0])
-                }
-            }
+    /// stm32f746 Discovery board. Fails unless it is FT6X06_ID, FT6X36_ID or FT5336_ID.
+    pub fn chip_id(&self, i2c: &mut I2C) -> Result<u8, Error<E, PinE>> {
+        let id = self.read_register::<ChipId>(i2c)?.value();
+        if !matches!(id, FT6X06_ID | FT6X36_ID | FT5336_ID) {
+            return Err(Error::WrongChipId(id));
         }
+        Ok(id)
+    }
+
+    /// Read the chip ID, firmware, library and vendor versions in one
+    /// transfer. The chip ID is reported as read, not checked.
+    pub fn device_info(&self, i2c: &mut I2C) -> Result<DeviceInfo, Error<E, PinE>> {
+        let mut buf = [0; FT6X06_INFO_LEN];
+        self.read_regs(i2c, FT6X06_LIB_VER_H_REG, &mut buf)?;
+        Ok(DeviceInfo::from_registers(&buf))
     }
 
     /// Is the device being touched? If so, how many fingers?
-    pub fn detect_touch(&mut self, i2c: &mut I2C) -> Result<u8, E> {
+    pub fn detect_touch(&mut self, i2c: &mut I2C) -> Result<u8, Error<E, PinE>> {
         let ntouch = loop {
             let n = self.td_status(i2c)?;
             if n > 0 {
                 break n;
             }
         };
-        assert!(ntouch <= FT6X06_MAX_NB_TOUCH as u8);
+        if ntouch as usize > N {
+            return Err(Error::InvalidTouchCount(ntouch));
+        }
         Ok(ntouch)
     }
 
-    /// Retrieve the FT6X06 firmware id
-    pub fn firmware_id(&mut self, i2c: &mut I2C) -> Result<u8, &str> {
-        let mut buf: [u8; 1] = [0];
-        match i2c.write_read(self.addr, &[FT6X06_FIRMID_REG], &mut buf) {
-            Err(_e) => Err("Error getting firmware ID"),
-            Ok(_d) => Ok(buf[0]),
+    /// Like [`detect_touch`](Self::detect_touch), but gives up with
+    /// [`Error::Timeout`] when nobody touches the screen within `timeout_ms`.
+    pub fn wait_for_touch(
+        &mut self,
+        i2c: &mut I2C,
+        timeout_ms: u32,
+        delay_source: &mut impl DelayNs,
+    ) -> Result<u8, Error<E, PinE>> {
+        let mut waited_ms = 0;
+        loop {
+            let ntouch = self.td_status(i2c)?;
+            if ntouch as usize > N {
+                return Err(Error::InvalidTouchCount(ntouch));
+            }
+            if ntouch > 0 {
+                return Ok(ntouch);
+            }
+            if waited_ms >= timeout_ms {
+                return Err(Error::Timeout);
+            }
+            delay_source.delay_ms(FT6X06_POLL_INTERVAL_MS);
+            waited_ms += FT6X06_POLL_INTERVAL_MS;
         }
     }
 
-    /// Retrieve the Gesture Init variable
-    pub fn gesture_radian_read(&mut self, i2c: &mut I2C) -> Result<u8, &str> {
-        let mut buf: [u8; 1] = [0];
-        match i2c.write_read(self.addr, &[FT6X06_RADIAN_VALUE_REG], &mut buf) {
-            Err(_e) => Err("Error getting Gesture Init: RADIAN VALUE REG"),
-            Ok(_d) => Ok(buf[0]),
+    /// Read the touch points without waiting. When nobody touches the screen
+    /// the result is returned at once and is empty.
+    pub fn try_read_touches(&mut self, i2c: &mut I2C) -> Result<MultiTouch<N>, Error<E, PinE>> {
+        self.get_multi_touch(i2c)
+    }
+
+    /// Read the current power mode
+    pub fn power_mode(&self, i2c: &mut I2C) -> Result<PowerMode, Error<E, PinE>> {
+        let pwr_mode: PwrMode = self.read_register(i2c)?;
+        pwr_mode.power_mode().ok_or(Error::UnexpectedValue {
+            reg: PwrMode::ADDR,
+            value: pwr_mode.bits(),
+        })
+    }
+
+    /// Switch the power mode. Returns whether the controller changed state.
+    ///
+    /// A hibernating controller does not answer on I2C, so entering
+    /// Hibernate cannot be read back and leaving it needs [`wake`](Self::wake).
+    pub fn set_power_mode(&self, i2c: &mut I2C, mode: PowerMode) -> Result<bool, Error<E, PinE>> {
+        if self.power_mode(i2c)? == mode {
+            return Ok(false);
         }
+
+        let mut pwr_mode = PwrMode::default();
+        pwr_mode.set_power_mode(mode);
+        self.write_register(i2c, pwr_mode)?;
+
+        if mode == PowerMode::Hibernate {
+            return Ok(true);
+        }
+        Ok(self.power_mode(i2c)? == mode)
     }
 
-    /// Write the Gesture Init variable
-    pub fn gesture_radian_write(&self, i2c: &mut I2C, value: u8) -> Result<bool, E> {
-        let mut buf: [u8; 1] = [value];
+    /// Wake the controller from Hibernate with a [`long_hard_reset`] and wait
+    /// until it answers again. Returns whether it came back in Active mode.
+    pub fn wake<RST: OutputPin>(
+        &self,
+        i2c: &mut I2C,
+        rst: &mut RST,
+        delay_source: &mut impl DelayNs,
+    ) -> Result<bool, Error<E, PinE>> {
+        long_hard_reset(rst, delay_source).map_err(|e| Error::ResetPin(e.kind()))?;
+        delay_source.delay_ms(FT6X06_STARTUP_TIME_MS);
+        Ok(self.power_mode(i2c)? == PowerMode::Active)
+    }
 
-        i2c.write_read(self.addr, &[FT6X06_RADIAN_VALUE_REG], &mut buf)?;
+    /// Read the scan-rate and auto-monitor configuration
+    pub fn read_scan_config(&self, i2c: &mut I2C) -> Result<ScanConfig, Error<E, PinE>> {
+        Ok(ScanConfig {
+            active_rate: self.read_register::<PeriodActive>(i2c)?.value(),
+            monitor_rate: self.read_register::<PeriodMonitor>(i2c)?.value(),
+            monitor_timeout_s: self.read_register::<TimeEnterMonitor>(i2c)?.value(),
+            keep_active: !self.read_register::<Ctrl>(i2c)?.auto_monitor(),
+        })
+    }
 
-        Ok(value == 0)
+    /// Write the scan-rate and auto-monitor configuration, verifying every
+    /// register. Values outside the datasheet ranges are rejected with
+    /// [`Error::OutOfRange`] before anything is written.
+    pub fn apply_scan_config(
+        &self,
+        i2c: &mut I2C,
+        config: &ScanConfig,
+    ) -> Result<(), Error<E, PinE>> {
+        config
+            .validate()
+            .map_err(|(reg, value)| Error::OutOfRange { reg, value })?;
+
+        self.modify_register::<PeriodActive>(i2c, |r| r.set_value(config.active_rate), true)?;
+        self.modify_register::<PeriodMonitor>(i2c, |r| r.set_value(config.monitor_rate), true)?;
+        self.modify_register::<TimeEnterMonitor>(
+            i2c,
+            |r| r.set_value(config.monitor_timeout_s),
+            true,
+        )?;
+        self.modify_register::<Ctrl>(i2c, |r| r.set_auto_monitor(!config.keep_active), true)?;
+        Ok(())
     }
 
-    /// Fetch the touch data specified by touch_i
-    /// touch_i should go from 1 to FT6X06_MAX_NB_TOUCH
-    pub fn get_touch(&mut self, i2c: &mut I2C, touch_i: u8) -> Result<TouchState, E> {
-        let mut buf: [u8; 6] = [0; 6];
-        i2c.write_read(self.addr, &[FT6X06_P1_XH_REG + 6 * (touch_i - 1)], &mut buf)?;
+    /// Read the gesture recognition parameters
+    pub fn read_gesture_config(&self, i2c: &mut I2C) -> Result<GestureConfig, Error<E, PinE>> {
+        Ok(GestureConfig {
+            radian: self.read_register::<RadianValue>(i2c)?.value(),
+            offset_left_right: self.read_register::<OffsetLeftRight>(i2c)?.value(),
+            offset_up_down: self.read_register::<OffsetUpDown>(i2c)?.value(),
+            distance_left_right: self.read_register::<DistanceLeftRight>(i2c)?.value(),
+            distance_up_down: self.read_register::<DistanceUpDown>(i2c)?.value(),
+            distance_zoom: self.read_register::<DistanceZoom>(i2c)?.value(),
+        })
+    }
 
-        // Tried copying the c code literally here. It makes no difference though
-        let x: u16 = (FT6X06_P1_XH_TP_BIT_MASK & buf[0]) as u16 * 256 + buf[1] as u16;
-        let y: u16 = (FT6X06_P1_YH_TP_BIT_MASK & buf[2]) as u16 * 256 + buf[3] as u16;
+    /// Write the gesture recognition parameters, verifying every register
+    pub fn apply_gesture_config(
+        &self,
+        i2c: &mut I2C,
+        config: &GestureConfig,
+    ) -> Result<(), Error<E, PinE>> {
+        self.modify_register::<RadianValue>(i2c, |r| r.set_value(config.radian), true)?;
+        self.modify_register::<OffsetLeftRight>(
+            i2c,
+            |r| r.set_value(config.offset_left_right),
+            true,
+        )?;
+        self.modify_register::<OffsetUpDown>(i2c, |r| r.set_value(config.offset_up_down), true)?;
+        self.modify_register::<DistanceLeftRight>(
+            i2c,
+            |r| r.set_value(config.distance_left_right),
+            true,
+        )?;
+        self.modify_register::<DistanceUpDown>(
+            i2c,
+            |r| r.set_value(config.distance_up_down),
+            true,
+        )?;
+        self.modify_register::<DistanceZoom>(i2c, |r| r.set_value(config.distance_zoom), true)?;
+        Ok(())
+    }
 
-        Ok(TouchState {
-            detected: true,
-            x,
-            y,
-            weight: buf[4],
-            misc: buf[5],
+    /// Snapshot every configurable register
+    pub fn read_config(&mut self, i2c: &mut I2C) -> Result<DeviceConfig, Error<E, PinE>> {
+        Ok(DeviceConfig {
+            threshold: self.threshold(i2c)?,
+            filter_coefficient: self.filter_coefficient(i2c)?,
+            scan: self.read_scan_config(i2c)?,
+            interrupt_mode: self.interrupt_mode(i2c)?,
+            gesture: if self.has_gestures() {
+                self.read_gesture_config(i2c)?
+            } else {
+                GestureConfig::default()
+            },
         })
     }
 
-    /// Fetch the touch data specified by touch_i
-    /// touch_i should go from 1 to FT6X06_MAX_NB_TOUCH
-    pub fn get_multi_touch(&mut self, i2c: &mut I2C, touch_i: u8) -> Result<MultiTouch, E> {
-        let mut buf: [u8; 12] = [0; 12];
-        i2c.write_read(self.addr, &[FT6X06_P1_XH_REG + 6 * (touch_i - 1)], &mut buf)?;
+    /// Restore a snapshot taken with [`read_config`](Self::read_config),
+    /// verifying every register
+    pub fn apply_config(
+        &mut self,
+        i2c: &mut I2C,
+        config: &DeviceConfig,
+    ) -> Result<(), Error<E, PinE>> {
+        self.apply_scan_config(i2c, &config.scan)?;
+        self.set_threshold(i2c, config.threshold)?;
+        self.set_filter_coefficient(i2c, config.filter_coefficient)?;
+        self.set_interrupt_mode(i2c, config.interrupt_mode)?;
+        if self.has_gestures() {
+            self.apply_gesture_config(i2c, &config.gesture)?;
+        }
+        Ok(())
+    }
 
-        let mut x: [u16; FT6X06_MAX_NB_TOUCH] = [0; FT6X06_MAX_NB_TOUCH];
-        let mut y: [u16; FT6X06_MAX_NB_TOUCH] = [0; FT6X06_MAX_NB_TOUCH];
-        let mut weight: [u16; FT6X06_MAX_NB_TOUCH] = [0; FT6X06_MAX_NB_TOUCH];
-        let mut misc: [u16; FT6X06_MAX_NB_TOUCH] = [0; FT6X06_MAX_NB_TOUCH];
+    /// Read the touch detection threshold
+    pub fn threshold(&self, i2c: &mut I2C) -> Result<u8, Error<E, PinE>> {
+        Ok(self.read_register::<ThGroup>(i2c)?.threshold())
+    }
+
+    /// Set the touch detection threshold. A lower threshold detects lighter
+    /// touches, e.g. through thick cover glass.
+    pub fn set_threshold(&self, i2c: &mut I2C, threshold: u8) -> Result<(), Error<E, PinE>> {
+        self.modify_register::<ThGroup>(i2c, |r| r.set_threshold(threshold), true)?;
+        Ok(())
+    }
+
+    /// Read the filter function coefficient
+    pub fn filter_coefficient(&self, i2c: &mut I2C) -> Result<u8, Error<E, PinE>> {
+        Ok(self.read_register::<ThDiff>(i2c)?.value())
+    }
 
-        let mut it: usize = 0;
-        for i in 0..FT6X06_MAX_NB_TOUCH {
-            x[i] = (FT6X06_P1_XH_TP_BIT_MASK & buf[0 + it]) as u16 * 256 + buf[1 + it] as u16;
-            y[i] = (FT6X06_P1_YH_TP_BIT_MASK & buf[2 + it]) as u16 * 256 + buf[3 + it] as u16;
-            weight[i] = buf[4 + it] as u16;
-            misc[i] = buf[5 + it] as u16;
-            it = it + 6;
+    /// Set the filter function coefficient
+    pub fn set_filter_coefficient(&self, i2c: &mut I2C, value: u8) -> Result<(), Error<E, PinE>> {
+        self.modify_register::<ThDiff>(i2c, |r| r.set_value(value), true)?;
+        Ok(())
+    }
+
+    /// Step through `thresholds`, sampling `samples` scans `interval_ms`
+    /// apart at each one, and hand the detection statistics of every
+    /// threshold to `report`. The threshold in use before the sweep is
+    /// restored afterwards, also when the sweep fails; the error of the
+    /// sweep takes precedence over one from restoring.
+    pub fn sweep_threshold(
+        &mut self,
+        i2c: &mut I2C,
+        thresholds: impl IntoIterator<Item = u8>,
+        samples: u16,
+        interval_ms: u32,
+        delay_source: &mut impl DelayNs,
+        mut report: impl FnMut(ThresholdStats),
+    ) -> Result<(), Error<E, PinE>> {
+        let original = self.threshold(i2c)?;
+        let result = self.run_sweep(
+            i2c,
+            thresholds,
+            samples,
+            interval_ms,
+            delay_source,
+            &mut report,
+        );
+        let restored = self.set_threshold(i2c, original);
+        result.and(restored)
+    }
+
+    fn run_sweep(
+        &mut self,
+        i2c: &mut I2C,
+        thresholds: impl IntoIterator<Item = u8>,
+        samples: u16,
+        interval_ms: u32,
+        delay_source: &mut impl DelayNs,
+        report: &mut impl FnMut(ThresholdStats),
+    ) -> Result<(), Error<E, PinE>> {
+        for threshold in thresholds {
+            self.set_threshold(i2c, threshold)?;
+            let mut stats = ThresholdStats::new(threshold);
+            for _ in 0..samples {
+                delay_source.delay_ms(interval_ms);
+                stats.add(&self.read_report(i2c)?);
+            }
+            report(stats);
         }
+        Ok(())
+    }
 
-        Ok(MultiTouch {
-            detected: true,
-            touch_x: x,
-            touch_y: y,
-            touch_weight: weight,
-            touch_area: misc,
-        })
+    /// Read the current operating state register
+    pub fn state(&self, i2c: &mut I2C) -> Result<u8, Error<E, PinE>> {
+        Ok(self.read_register::<State>(i2c)?.value())
     }
 
-    /// Get gestures interpreted by touchscreen
-    pub fn get_gesture(&mut self, i2c: &mut I2C) -> Result<GestureKind, E> {
-        let mut buf: [u8; 1] = [0];
-        i2c.write_read(self.addr, &[FT6X06_GEST_ID_REG], &mut buf)?;
+    /// Retrieve the FT6X06 firmware id
+    pub fn firmware_id(&mut self, i2c: &mut I2C) -> Result<u8, Error<E, PinE>> {
+        Ok(self.read_register::<FirmId>(i2c)?.value())
+    }
 
-        let g: GestureKind = match buf[0] {
-            FT6X06_GEST_ID_NO_GESTURE => GestureKind::None,
-            FT6X06_GEST_ID_MOVE_UP => GestureKind::Up,
-            FT6X06_GEST_ID_MOVE_RIGHT => GestureKind::Right,
-            FT6X06_GEST_ID_MOVE_DOWN => GestureKind::Down,
-            FT6X06_GEST_ID_MOVE_LEFT => GestureKind::Left,
-            FT6X06_GEST_ID_ZOOM_IN => GestureKind::ZoomIn,
-            FT6X06_GEST_ID_ZOOM_OUT => GestureKind::ZoomOut,
-            _ => GestureKind::Fault,
-        };
-        Ok(g)
+    /// Retrieve the Gesture Init variable
+    pub fn gesture_radian_read(&mut self, i2c: &mut I2C) -> Result<u8, Error<E, PinE>> {
+        Ok(self.read_register::<RadianValue>(i2c)?.value())
+    }
+
+    /// Write the Gesture Init variable
+    pub fn gesture_radian_write(&self, i2c: &mut I2C, value: u8) -> Result<bool, Error<E, PinE>> {
+        self.modify_register::<RadianValue>(i2c, |r| r.set_value(value), true)?;
+
+        Ok(value == 0)
+    }
+
+    /// Fetch the touch data of the given slot
+    pub fn get_touch(
+        &mut self,
+        i2c: &mut I2C,
+        slot: TouchSlot<N>,
+    ) -> Result<TouchState, Error<E, PinE>> {
+        Ok(self.get_raw_touch(i2c, slot)?.to_panel(&self.panel))
+    }
+
+    /// Fetch the touch data of the given slot in raw controller coordinates:
+    /// no calibration, clamping or transform. Use it to take calibration
+    /// samples. Fails with [`Error::InvalidSlot`] for a slot past the points
+    /// the detected variant tracks.
+    pub fn get_raw_touch(
+        &mut self,
+        i2c: &mut I2C,
+        slot: TouchSlot<N>,
+    ) -> Result<TouchState, Error<E, PinE>> {
+        if slot.index() >= self.slot_count() {
+            return Err(Error::InvalidSlot(slot.index()));
+        }
+        let mut buf: [u8; 6] = [0; 6];
+        self.read_regs(i2c, slot.xh_reg(), &mut buf)?;
+
+        Ok(TouchState::from_registers(&buf))
+    }
+
+    /// Fetch the touch points that are present, read together with the
+    /// touch count in one transfer
+    pub fn get_multi_touch(&mut self, i2c: &mut I2C) -> Result<MultiTouch<N>, Error<E, PinE>> {
+        let report = self.read_report(i2c)?;
+        Ok(MultiTouch::from_points(report.points()))
+    }
+
+    /// Read the gesture, the touch count and the touch points of one scan
+    /// in a single I2C transfer
+    pub fn read_report(&mut self, i2c: &mut I2C) -> Result<TouchReport<N>, Error<E, PinE>> {
+        let mut buf = [0; FT6X06_MAX_REPORT_LEN];
+        let buf = &mut buf[..TouchReport::<N>::LEN];
+        self.read_regs(i2c, FT6X06_DEV_MODE_REG, buf)?;
+        let mut report = TouchReport::from_registers(buf).map_err(Error::InvalidTouchCount)?;
+        if !self.has_gestures() {
+            report.gesture = GestureKind::None;
+        }
+        Ok(report.to_panel(&self.panel))
+    }
+
+    /// Get gestures interpreted by touchscreen
+    pub fn get_gesture(&mut self, i2c: &mut I2C) -> Result<GestureKind, Error<E, PinE>> {
+        if !self.has_gestures() {
+            return Ok(GestureKind::None);
+        }
+        let gest_id: GestId = self.read_register(i2c)?;
+        Ok(gest_id.gesture())
     }
 
-    pub fn get_coordinates(&mut self, i2c: &mut I2C) -> Result<(u16, u16), E> {
-        self.wait_touch_interrupt();
+    pub fn get_coordinates(&mut self, i2c: &mut I2C) -> Result<(u16, u16), Error<E, PinE>> {
+        self.wait_touch_interrupt()?;
         let _ntouch = self.detect_touch(i2c)?;
-        let pt = self.get_touch(i2c, 1)?;
+        let pt = self.get_touch(i2c, TouchSlot::P1)?;
         Ok((pt.x, pt.y))
     }
 
@@ -0,0 +1,435 @@
+//! FT6x06 driver that owns its I2C bus.
+//!
+//! [`Ft6X06`] borrows the bus on every call so it can be shared by hand with
+//! other devices. When the touch controller is the only user of the bus,
+//! [`Ft6X06Owned`] keeps the bus inside the driver and offers the same calls
+//! without the `i2c` argument. [`Ft6X06Owned::release`] gives the bus and the
+//! interrupt pin back.
+
+use crate::calibration::{Calibration, CalibrationError, Residual, Sample};
+use crate::constant::FT6X06_MAX_NB_TOUCH;
+use crate::register::{Register, Writable};
+use crate::{
+    ChipVariant, DeviceConfig, DeviceInfo, Error, Ft6X06, Ft6x06Capabilities, GestureConfig,
+    GestureKind, InitOptions, InterruptMode, MultiTouch, PowerMode, ScanConfig, ThresholdStats,
+    TouchReport, TouchSlot, TouchState, Transform,
+};
+#[cfg(feature = "embedded-graphics")]
+use embedded_graphics::geometry::{Dimensions, Point};
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "storage")]
+use embedded_storage::nor_flash::ReadNorFlash;
+
+/// FT6x06 driver object owning the I2C bus and the touch interrupt pin.
+/// `N` is the number of touch points the controller tracks, as for [`Ft6X06`].
+pub struct Ft6X06Owned<I2C, TouchInterruptPin, const N: usize = FT6X06_MAX_NB_TOUCH> {
+    i2c: I2C,
+    driver: Ft6X06<I2C, TouchInterruptPin, N>,
+}
+
+impl<I2C, TouchInterruptPin, E, PinE> Ft6X06Owned<I2C, TouchInterruptPin>
+where
+    I2C: I2c<Error = E>,
+    TouchInterruptPin: InputPin<Error = PinE>,
+{
+    /// Creates a new sensor that takes ownership of the I2C peripheral and
+    /// the touch interrupt pin.
+    pub fn new(i2c: I2C, addr: u8, interrupt: TouchInterruptPin) -> Result<Self, Error<E, PinE>> {
+        Self::with_max_touch(i2c, addr, interrupt)
+    }
+}
+
+impl<I2C, TouchInterruptPin, E, PinE, const N: usize> Ft6X06Owned<I2C, TouchInterruptPin, N>
+where
+    I2C: I2c<Error = E>,
+    TouchInterruptPin: InputPin<Error = PinE>,
+{
+    /// Creates a new sensor tracking up to `N` touch points that takes
+    /// ownership of the I2C peripheral and the touch interrupt pin.
+    pub fn with_max_touch(
+        i2c: I2C,
+        addr: u8,
+        interrupt: TouchInterruptPin,
+    ) -> Result<Self, Error<E, PinE>> {
+        let driver = Ft6X06::with_max_touch(&i2c, addr, interrupt)?;
+        Ok(Ft6X06Owned { i2c, driver })
+    }
+
+    /// Set the size of the panel the controller is mounted on.
+    /// Reported coordinates are clamped to it. Without a panel size the
+    /// driver passes the full 12 bit coordinate range through unclamped.
+    pub fn with_panel_size(mut self, width: u16, height: u16) -> Result<Self, Error<E, PinE>> {
+        self.driver = self.driver.with_panel_size(width, height)?;
+        Ok(self)
+    }
+
+    /// Transform applied to every touch point
+    pub fn transform(&self) -> Transform {
+        self.driver.transform()
+    }
+
+    /// Change the transform applied to every touch point; needs the panel
+    /// size, see [`Ft6X06::set_transform`]
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.driver.set_transform(transform)
+    }
+
+    /// Calibration applied to every touch point
+    pub fn calibration(&self) -> Option<Calibration> {
+        self.driver.calibration()
+    }
+
+    /// Change the calibration applied to every touch point
+    pub fn set_calibration(&mut self, calibration: Option<Calibration>) {
+        self.driver.set_calibration(calibration)
+    }
+
+    /// Fit a calibration to samples read with
+    /// [`get_raw_touch`](Self::get_raw_touch) whose targets are display
+    /// positions under the current transform
+    pub fn fit_calibration(
+        &self,
+        samples: &[Sample],
+    ) -> Result<(Calibration, Residual), CalibrationError> {
+        self.driver.fit_calibration(samples)
+    }
+
+    /// Scale a touch point from the panel into the bounding box of `target`
+    #[cfg(feature = "embedded-graphics")]
+    pub fn map_to_target<D: Dimensions>(&self, touch: &TouchState, target: &D) -> Point {
+        self.driver.map_to_target(touch, target)
+    }
+
+    /// Destroy the driver and give back the I2C bus and interrupt pin
+    pub fn release(self) -> (I2C, TouchInterruptPin) {
+        (self.i2c, self.driver.interrupt)
+    }
+
+    /// Initialise device and disable interupt mode.
+    pub fn init(&mut self, delay_source: &mut impl DelayNs) -> Result<(), Error<E, PinE>> {
+        self.driver.init(&mut self.i2c, delay_source)
+    }
+
+    /// Bring the controller up after power-on as `options` say.
+    /// Returns the detected variant.
+    pub fn init_with(
+        &mut self,
+        delay_source: &mut impl DelayNs,
+        options: &InitOptions,
+    ) -> Result<ChipVariant, Error<E, PinE>> {
+        self.driver.init_with(&mut self.i2c, delay_source, options)
+    }
+
+    /// Like [`init_with`](Self::init_with), after a long hard reset through `rst`
+    pub fn reset_and_init<RST: OutputPin>(
+        &mut self,
+        rst: &mut RST,
+        delay_source: &mut impl DelayNs,
+        options: &InitOptions,
+    ) -> Result<ChipVariant, Error<E, PinE>> {
+        self.driver
+            .reset_and_init(&mut self.i2c, rst, delay_source, options)
+    }
+
+    /// Like [`init_with`](Self::init_with), with the calibration and config
+    /// of the settings record at `offset` in `flash`
+    #[cfg(feature = "storage")]
+    pub fn init_from_storage<S: ReadNorFlash>(
+        &mut self,
+        delay_source: &mut impl DelayNs,
+        flash: &mut S,
+        offset: u32,
+        options: &InitOptions,
+    ) -> Result<(ChipVariant, bool), Error<E, PinE>> {
+        self.driver
+            .init_from_storage(&mut self.i2c, delay_source, flash, offset, options)
+    }
+
+    /// Delay through the given delay source
+    pub fn delay_ms(&mut self, delay_source: &mut impl DelayNs, delay: u32) {
+        self.driver.delay_ms(delay_source, delay)
+    }
+
+    /// Returns the structure that contains all the preset capabilities
+    /// of the FT6X06
+    pub fn get_capabilities(&self) -> Ft6x06Capabilities {
+        self.driver.get_capabilities()
+    }
+
+    /// Variant found by the last [`detect_variant`](Self::detect_variant)
+    pub fn variant(&self) -> ChipVariant {
+        self.driver.variant()
+    }
+
+    /// Identify the controller variant and use its capabilities from then on
+    pub fn detect_variant(&mut self) -> Result<ChipVariant, Error<E, PinE>> {
+        self.driver.detect_variant(&mut self.i2c)
+    }
+
+    /// Read whether the FT6X06 is in dev mode or not
+    pub fn dev_mode_r(&mut self) -> Result<u8, Error<E, PinE>> {
+        self.driver.dev_mode_r(&mut self.i2c)
+    }
+
+    /// Put the FT6X06 into dev mode
+    pub fn dev_mode_w(&mut self, value: u8) -> Result<bool, Error<E, PinE>> {
+        self.driver.dev_mode_w(&mut self.i2c, value)
+    }
+
+    /// Get the value of an 8 bit register
+    pub fn get_u8_reg(&mut self, reg: u8) -> Result<u8, Error<E, PinE>> {
+        self.driver.get_u8_reg(&mut self.i2c, reg)
+    }
+
+    /// Set the value of an 8 bit register
+    pub fn set_u8_reg(&mut self, reg: u8, val: u8) -> Result<(), Error<E, PinE>> {
+        self.driver.set_u8_reg(&mut self.i2c, reg, val)
+    }
+
+    /// Read-modify-write the bits under `mask` of a register
+    pub fn modify_reg(&mut self, reg: u8, mask: u8, value: u8) -> Result<u8, Error<E, PinE>> {
+        self.driver.modify_reg(&mut self.i2c, reg, mask, value)
+    }
+
+    /// Write a field of a register, optionally verifying it by reading back
+    pub fn write_field(
+        &mut self,
+        reg: u8,
+        mask: u8,
+        shift: u8,
+        value: u8,
+        verify: bool,
+    ) -> Result<(), Error<E, PinE>> {
+        self.driver
+            .write_field(&mut self.i2c, reg, mask, shift, value, verify)
+    }
+
+    /// Read a typed register
+    pub fn read_register<R: Register>(&mut self) -> Result<R, Error<E, PinE>> {
+        self.driver.read_register(&mut self.i2c)
+    }
+
+    /// Write a typed register
+    pub fn write_register<R: Writable>(&mut self, reg: R) -> Result<(), Error<E, PinE>> {
+        self.driver.write_register(&mut self.i2c, reg)
+    }
+
+    /// Read-modify-write a typed register, optionally verifying it by
+    /// reading back
+    pub fn modify_register<R: Writable>(
+        &mut self,
+        f: impl FnOnce(&mut R),
+        verify: bool,
+    ) -> Result<R, Error<E, PinE>> {
+        self.driver.modify_register(&mut self.i2c, f, verify)
+    }
+
+    /// Read the interrupt mode from the controller
+    pub fn interrupt_mode(&mut self) -> Result<InterruptMode, Error<E, PinE>> {
+        self.driver.interrupt_mode(&mut self.i2c)
+    }
+
+    /// Select how the controller drives the INT pin
+    pub fn set_interrupt_mode(&mut self, mode: InterruptMode) -> Result<(), Error<E, PinE>> {
+        self.driver.set_interrupt_mode(&mut self.i2c, mode)
+    }
+
+    /// Wait for the touchscreen interrupt to indicate touches
+    pub fn wait_touch_interrupt(&mut self) -> Result<(), Error<E, PinE>> {
+        self.driver.wait_touch_interrupt()
+    }
+
+    /// Wait for the touchscreen interrupt, giving up after `timeout_ms`
+    pub fn wait_touch_interrupt_timeout(
+        &mut self,
+        timeout_ms: u32,
+        delay_source: &mut impl DelayNs,
+    ) -> Result<(), Error<E, PinE>> {
+        self.driver
+            .wait_touch_interrupt_timeout(timeout_ms, delay_source)
+    }
+
+    /// Run an internal calibration on the FT6X06
+    pub fn ts_calibration(
+        &mut self,
+        delay_source: &mut impl DelayNs,
+    ) -> Result<bool, Error<E, PinE>> {
+        self.driver.ts_calibration(&mut self.i2c, delay_source)
+    }
+
+    /// Read the touch device status
+    pub fn td_status(&mut self) -> Result<u8, Error<E, PinE>> {
+        self.driver.td_status(&mut self.i2c)
+    }
+
+    /// Read the touch device chip ID
+    pub fn chip_id(&mut self) -> Result<u8, Error<E, PinE>> {
+        self.driver.chip_id(&mut self.i2c)
+    }
+
+    /// Read the chip ID, firmware, library and vendor versions in one transfer
+    pub fn device_info(&mut self) -> Result<DeviceInfo, Error<E, PinE>> {
+        self.driver.device_info(&mut self.i2c)
+    }
+
+    /// Is the device being touched? If so, how many fingers?
+    pub fn detect_touch(&mut self) -> Result<u8, Error<E, PinE>> {
+        self.driver.detect_touch(&mut self.i2c)
+    }
+
+    /// Wait for a touch, giving up after `timeout_ms`
+    pub fn wait_for_touch(
+        &mut self,
+        timeout_ms: u32,
+        delay_source: &mut impl DelayNs,
+    ) -> Result<u8, Error<E, PinE>> {
+        self.driver
+            .wait_for_touch(&mut self.i2c, timeout_ms, delay_source)
+    }
+
+    /// Read the touch points without waiting
+    pub fn try_read_touches(&mut self) -> Result<MultiTouch<N>, Error<E, PinE>> {
+        self.driver.try_read_touches(&mut self.i2c)
+    }
+
+    /// Read the current power mode
+    pub fn power_mode(&mut self) -> Result<PowerMode, Error<E, PinE>> {
+        self.driver.power_mode(&mut self.i2c)
+    }
+
+    /// Switch the power mode. Returns whether the controller changed state.
+    pub fn set_power_mode(&mut self, mode: PowerMode) -> Result<bool, Error<E, PinE>> {
+        self.driver.set_power_mode(&mut self.i2c, mode)
+    }
+
+    /// Wake the controller from Hibernate through a reset
+    pub fn wake<RST: OutputPin>(
+        &mut self,
+        rst: &mut RST,
+        delay_source: &mut impl DelayNs,
+    ) -> Result<bool, Error<E, PinE>> {
+        self.driver.wake(&mut self.i2c, rst, delay_source)
+    }
+
+    /// Read the scan-rate and auto-monitor configuration
+    pub fn read_scan_config(&mut self) -> Result<ScanConfig, Error<E, PinE>> {
+        self.driver.read_scan_config(&mut self.i2c)
+    }
+
+    /// Write the scan-rate and auto-monitor configuration
+    pub fn apply_scan_config(&mut self, config: &ScanConfig) -> Result<(), Error<E, PinE>> {
+        self.driver.apply_scan_config(&mut self.i2c, config)
+    }
+
+    /// Read the gesture recognition parameters
+    pub fn read_gesture_config(&mut self) -> Result<GestureConfig, Error<E, PinE>> {
+        self.driver.read_gesture_config(&mut self.i2c)
+    }
+
+    /// Write the gesture recognition parameters
+    pub fn apply_gesture_config(&mut self, config: &GestureConfig) -> Result<(), Error<E, PinE>> {
+        self.driver.apply_gesture_config(&mut self.i2c, config)
+    }
+
+    /// Snapshot every configurable register
+    pub fn read_config(&mut self) -> Result<DeviceConfig, Error<E, PinE>> {
+        self.driver.read_config(&mut self.i2c)
+    }
+
+    /// Restore a snapshot taken with [`read_config`](Self::read_config)
+    pub fn apply_config(&mut self, config: &DeviceConfig) -> Result<(), Error<E, PinE>> {
+        self.driver.apply_config(&mut self.i2c, config)
+    }
+
+    /// Read the touch detection threshold
+    pub fn threshold(&mut self) -> Result<u8, Error<E, PinE>> {
+        self.driver.threshold(&mut self.i2c)
+    }
+
+    /// Set the touch detection threshold
+    pub fn set_threshold(&mut self, threshold: u8) -> Result<(), Error<E, PinE>> {
+        self.driver.set_threshold(&mut self.i2c, threshold)
+    }
+
+    /// Read the filter function coefficient
+    pub fn filter_coefficient(&mut self) -> Result<u8, Error<E, PinE>> {
+        self.driver.filter_coefficient(&mut self.i2c)
+    }
+
+    /// Set the filter function coefficient
+    pub fn set_filter_coefficient(&mut self, value: u8) -> Result<(), Error<E, PinE>> {
+        self.driver.set_filter_coefficient(&mut self.i2c, value)
+    }
+
+    /// Sweep the touch threshold and report detection statistics
+    pub fn sweep_threshold(
+        &mut self,
+        thresholds: impl IntoIterator<Item = u8>,
+        samples: u16,
+        interval_ms: u32,
+        delay_source: &mut impl DelayNs,
+        report: impl FnMut(ThresholdStats),
+    ) -> Result<(), Error<E, PinE>> {
+        self.driver.sweep_threshold(
+            &mut self.i2c,
+            thresholds,
+            samples,
+            interval_ms,
+            delay_source,
+            report,
+        )
+    }
+
+    /// Read the current operating state register
+    pub fn state(&mut self) -> Result<u8, Error<E, PinE>> {
+        self.driver.state(&mut self.i2c)
+    }
+
+    /// Retrieve the FT6X06 firmware id
+    pub fn firmware_id(&mut self) -> Result<u8, Error<E, PinE>> {
+        self.driver.firmware_id(&mut self.i2c)
+    }
+
+    /// Retrieve the Gesture Init variable
+    pub fn gesture_radian_read(&mut self) -> Result<u8, Error<E, PinE>> {
+        self.driver.gesture_radian_read(&mut self.i2c)
+    }
+
+    /// Write the Gesture Init variable
+    pub fn gesture_radian_write(&mut self, value: u8) -> Result<bool, Error<E, PinE>> {
+        self.driver.gesture_radian_write(&mut self.i2c, value)
+    }
+
+    /// Fetch the touch data of the given slot
+    pub fn get_touch(&mut self, slot: TouchSlot<N>) -> Result<TouchState, Error<E, PinE>> {
+        self.driver.get_touch(&mut self.i2c, slot)
+    }
+
+    /// Fetch the touch data of the given slot in raw controller coordinates
+    pub fn get_raw_touch(&mut self, slot: TouchSlot<N>) -> Result<TouchState, Error<E, PinE>> {
+        self.driver.get_raw_touch(&mut self.i2c, slot)
+    }
+
+    /// Fetch the touch points that are present
+    pub fn get_multi_touch(&mut self) -> Result<MultiTouch<N>, Error<E, PinE>> {
+        self.driver.get_multi_touch(&mut self.i2c)
+    }
+
+    /// Read the gesture, the touch count and the touch points of one scan
+    /// in a single I2C transfer
+    pub fn read_report(&mut self) -> Result<TouchReport<N>, Error<E, PinE>> {
+        self.driver.read_report(&mut self.i2c)
+    }
+
+    /// Get gestures interpreted by touchscreen
+    pub fn get_gesture(&mut self) -> Result<GestureKind, Error<E, PinE>> {
+        self.driver.get_gesture(&mut self.i2c)
+    }
+
+    /// Wait for a touch and return the coordinates of the first point
+    pub fn get_coordinates(&mut self) -> Result<(u16, u16), Error<E, PinE>> {
+        self.driver.get_coordinates(&mut self.i2c)
+    }
+}
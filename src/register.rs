@@ -0,0 +1,321 @@
+//! Typed view of the FT6x06 register map.
+//!
+//! Every register has its own type that knows its address and exposes its
+//! fields through getters and setters, so masks and shifts live in one place.
+//! Registers the controller only reports implement [`Register`]; registers
+//! that can be configured also implement [`Writable`], so writing a read-only
+//! register does not compile.
+//!
+//! ```ignore
+//! let mut ctrl: Ctrl = touch.read_register(&mut i2c)?;
+//! ctrl.set_auto_monitor(false);
+//! touch.write_register(&mut i2c, ctrl)?;
+//!
+//! // or in one go, verified by reading back
+//! touch.modify_register::<Ctrl>(&mut i2c, |r| r.set_auto_monitor(false), true)?;
+//! ```
+
+use crate::constant::*;
+use crate::{GestureKind, InterruptMode, PowerMode};
+
+/// A register of the FT6x06.
+pub trait Register: Copy {
+    /// Address of the register
+    const ADDR: u8;
+
+    /// Build the register from its raw value
+    fn from_bits(bits: u8) -> Self;
+
+    /// Raw value of the register
+    fn bits(self) -> u8;
+}
+
+/// A register that can be written.
+pub trait Writable: Register {}
+
+macro_rules! register {
+    ($(#[$meta:meta])* $name:ident = $addr:expr) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+        pub struct $name(u8);
+
+        impl Register for $name {
+            const ADDR: u8 = $addr;
+
+            fn from_bits(bits: u8) -> Self {
+                $name(bits)
+            }
+
+            fn bits(self) -> u8 {
+                self.0
+            }
+        }
+    };
+    ($(#[$meta:meta])* writable $name:ident = $addr:expr) => {
+        register!($(#[$meta])* $name = $addr);
+
+        impl Writable for $name {}
+    };
+}
+
+/// Registers whose whole byte is a single value
+macro_rules! value_register {
+    ($(#[$meta:meta])* $name:ident = $addr:expr) => {
+        register!($(#[$meta])* $name = $addr);
+
+        impl $name {
+            /// Value of the register
+            pub fn value(self) -> u8 {
+                self.0
+            }
+        }
+    };
+    ($(#[$meta:meta])* writable $name:ident = $addr:expr) => {
+        register!($(#[$meta])* writable $name = $addr);
+
+        impl $name {
+            /// Value of the register
+            pub fn value(self) -> u8 {
+                self.0
+            }
+
+            /// Set the value of the register
+            pub fn set_value(&mut self, value: u8) {
+                self.0 = value;
+            }
+        }
+    };
+}
+
+fn get_field(bits: u8, mask: u8, shift: u8) -> u8 {
+    (bits & mask) >> shift
+}
+
+fn set_field(bits: u8, mask: u8, shift: u8, value: u8) -> u8 {
+    (bits & !mask) | ((value << shift) & mask)
+}
+
+register!(
+    /// Device mode (0x00)
+    writable DevMode = FT6X06_DEV_MODE_REG
+);
+
+impl DevMode {
+    /// Device mode field, FT6X06_DEV_MODE_WORKING or FT6X06_DEV_MODE_FACTORY
+    pub fn mode(self) -> u8 {
+        get_field(self.0, FT6X06_DEV_MODE_MASK, FT6X06_DEV_MODE_SHIFT)
+    }
+
+    /// Set the device mode field
+    pub fn set_mode(&mut self, mode: u8) {
+        self.0 = set_field(self.0, FT6X06_DEV_MODE_MASK, FT6X06_DEV_MODE_SHIFT, mode);
+    }
+}
+
+register!(
+    /// Gesture ID (0x01)
+    GestId = FT6X06_GEST_ID_REG
+);
+
+impl GestId {
+    /// Gesture reported by the controller
+    pub fn gesture(self) -> GestureKind {
+        GestureKind::from_gest_id(self.0)
+    }
+}
+
+register!(
+    /// Touch data status (0x02)
+    TdStatus = FT6X06_TD_STAT_REG
+);
+
+impl TdStatus {
+    /// Number of touch points currently reported
+    pub fn touch_points(self) -> u8 {
+        get_field(self.0, FT6X06_TD_STAT_MASK, FT6X06_TD_STAT_SHIFT)
+    }
+}
+
+register!(
+    /// Touch detection threshold (0x80)
+    writable ThGroup = FT6X06_TH_GROUP_REG
+);
+
+impl ThGroup {
+    /// Threshold for touch detection
+    pub fn threshold(self) -> u8 {
+        get_field(self.0, FT6X06_THRESHOLD_MASK, FT6X06_THRESHOLD_SHIFT)
+    }
+
+    /// Set the threshold for touch detection
+    pub fn set_threshold(&mut self, threshold: u8) {
+        self.0 = set_field(
+            self.0,
+            FT6X06_THRESHOLD_MASK,
+            FT6X06_THRESHOLD_SHIFT,
+            threshold,
+        );
+    }
+}
+
+value_register!(
+    /// Filter function coefficient (0x85)
+    writable ThDiff = FT6X06_TH_DIFF_REG
+);
+
+register!(
+    /// Control (0x86)
+    writable Ctrl = FT6X06_CTRL_REG
+);
+
+impl Ctrl {
+    /// Whether the controller switches to Monitor mode when there is no touching
+    pub fn auto_monitor(self) -> bool {
+        get_field(self.0, FT6X06_CTRL_MODE_MASK, FT6X06_CTRL_MODE_SHIFT)
+            == FT6X06_CTRL_KEEP_AUTO_SWITCH_MONITOR_MODE
+    }
+
+    /// Set whether the controller switches to Monitor mode when there is no touching
+    pub fn set_auto_monitor(&mut self, auto_monitor: bool) {
+        let mode = if auto_monitor {
+            FT6X06_CTRL_KEEP_AUTO_SWITCH_MONITOR_MODE
+        } else {
+            FT6X06_CTRL_KEEP_ACTIVE_MODE
+        };
+        self.0 = set_field(self.0, FT6X06_CTRL_MODE_MASK, FT6X06_CTRL_MODE_SHIFT, mode);
+    }
+}
+
+value_register!(
+    /// Time in seconds without touching before switching to Monitor mode (0x87)
+    writable TimeEnterMonitor = FT6X06_TIMEENTERMONITOR_REG
+);
+
+value_register!(
+    /// Report rate in Active mode (0x88)
+    writable PeriodActive = FT6X06_PERIODACTIVE_REG
+);
+
+value_register!(
+    /// Report rate in Monitor mode (0x89)
+    writable PeriodMonitor = FT6X06_PERIODMONITOR_REG
+);
+
+value_register!(
+    /// Minimum allowed angle while Rotating gesture mode (0x91)
+    writable RadianValue = FT6X06_RADIAN_VALUE_REG
+);
+
+value_register!(
+    /// Maximum offset while Moving Left and Moving Right gesture (0x92)
+    writable OffsetLeftRight = FT6X06_OFFSET_LEFT_RIGHT_REG
+);
+
+value_register!(
+    /// Maximum offset while Moving Up and Moving Down gesture (0x93)
+    writable OffsetUpDown = FT6X06_OFFSET_UP_DOWN_REG
+);
+
+value_register!(
+    /// Minimum distance while Moving Left and Moving Right gesture (0x94)
+    writable DistanceLeftRight = FT6X06_DISTANCE_LEFT_RIGHT_REG
+);
+
+value_register!(
+    /// Minimum distance while Moving Up and Moving Down gesture (0x95)
+    writable DistanceUpDown = FT6X06_DISTANCE_UP_DOWN_REG
+);
+
+value_register!(
+    /// Maximum distance while Zoom In and Zoom Out gesture (0x96)
+    writable DistanceZoom = FT6X06_DISTANCE_ZOOM_REG
+);
+
+value_register!(
+    /// High byte of the library version (0xA1)
+    LibVerH = FT6X06_LIB_VER_H_REG
+);
+
+value_register!(
+    /// Low byte of the library version (0xA2)
+    LibVerL = FT6X06_LIB_VER_L_REG
+);
+
+value_register!(
+    /// Chip vendor ID (0xA3)
+    Cipher = FT6X06_CIPHER_REG
+);
+
+register!(
+    /// Interrupt mode (0xA4)
+    writable GMode = FT6X06_GMODE_REG
+);
+
+impl GMode {
+    /// Interrupt mode field, FT6X06_G_MODE_INTERRUPT_POLLING or
+    /// FT6X06_G_MODE_INTERRUPT_TRIGGER
+    pub fn mode(self) -> u8 {
+        get_field(
+            self.0,
+            FT6X06_G_MODE_INTERRUPT_MASK,
+            FT6X06_G_MODE_INTERRUPT_SHIFT,
+        )
+    }
+
+    /// Set the interrupt mode field
+    pub fn set_mode(&mut self, mode: u8) {
+        self.0 = set_field(
+            self.0,
+            FT6X06_G_MODE_INTERRUPT_MASK,
+            FT6X06_G_MODE_INTERRUPT_SHIFT,
+            mode,
+        );
+    }
+
+    /// Interrupt mode, `None` for a value the datasheet does not define
+    pub fn interrupt_mode(self) -> Option<InterruptMode> {
+        InterruptMode::from_bits(self.mode())
+    }
+
+    /// Set the interrupt mode
+    pub fn set_interrupt_mode(&mut self, mode: InterruptMode) {
+        self.set_mode(mode.bits());
+    }
+}
+
+value_register!(
+    /// Power mode (0xA5)
+    writable PwrMode = FT6X06_PWR_MODE_REG
+);
+
+impl PwrMode {
+    /// Power mode, `None` for a value the datasheet does not define
+    pub fn power_mode(self) -> Option<PowerMode> {
+        PowerMode::from_bits(self.0)
+    }
+
+    /// Set the power mode
+    pub fn set_power_mode(&mut self, mode: PowerMode) {
+        self.0 = mode.bits();
+    }
+}
+
+value_register!(
+    /// Firmware version (0xA6)
+    FirmId = FT6X06_FIRMID_REG
+);
+
+value_register!(
+    /// Chip identification (0xA8)
+    ChipId = FT6X06_CHIP_ID_REG
+);
+
+value_register!(
+    /// Release code version (0xAF)
+    ReleaseCodeId = FT6X06_RELEASE_CODE_ID_REG
+);
+
+value_register!(
+    /// Current operating state (0xBC)
+    State = FT6X06_STATE_REG
+);
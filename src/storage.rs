@@ -0,0 +1,233 @@
+//! Persisting calibration and configuration in NOR flash.
+//!
+//! A tuned and calibrated unit should stay that way across power cycles.
+//! [`StoredSettings`] serializes the [`Calibration`] and the [`DeviceConfig`]
+//! into a small versioned record protected by a CRC-32, which is kept in
+//! flash through the `embedded_storage::nor_flash` traits.
+//!
+//! ```ignore
+//! storage::save(&mut flash, SETTINGS_OFFSET, &StoredSettings {
+//!     calibration: touch.calibration(),
+//!     config: touch.read_config(&mut i2c)?,
+//! })?;
+//!
+//! // at the next boot
+//! let (variant, restored) =
+//!     touch.init_from_storage(&mut i2c, &mut delay, &mut flash, SETTINGS_OFFSET, &InitOptions::new())?;
+//! ```
+//!
+//! The record occupies one erase sector of its own, starting at the offset
+//! given to [`save`] and [`load`].
+
+use crate::calibration::Calibration;
+use crate::{DeviceConfig, GestureConfig, InitOptions, InterruptMode, ScanConfig};
+use embedded_storage::nor_flash::{NorFlash, ReadNorFlash};
+
+/// Marks the start of a settings record
+pub const RECORD_MAGIC: [u8; 4] = *b"FT6S";
+
+/// Layout version of the record written by this driver
+pub const RECORD_VERSION: u8 = 1;
+
+/// Length of a settings record in bytes, including the CRC
+pub const RECORD_LEN: usize = 48;
+
+/// Bytes read from and written to flash: the record padded with 0xFF.
+/// The flash's READ_SIZE and WRITE_SIZE must divide it.
+pub const RECORD_CAPACITY: usize = 64;
+
+const FLAG_CALIBRATION: u8 = 0x01;
+
+/// Why a record could not be decoded
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RecordError {
+    /// The record does not start with RECORD_MAGIC, e.g. erased flash
+    BadMagic,
+    /// The record was written in a layout this driver does not know
+    UnsupportedVersion(u8),
+    /// The CRC does not match, the record is corrupt
+    BadCrc,
+    /// A field holds a value the driver would refuse to apply
+    InvalidValue,
+}
+
+/// Errors when saving or loading settings
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StorageError<E> {
+    /// Flash access failed
+    Flash(E),
+    /// The stored record is missing or corrupt
+    Record(RecordError),
+    /// The flash's read or write size does not divide RECORD_CAPACITY, or
+    /// its erase size is smaller than it
+    Alignment,
+}
+
+/// Everything that is kept across power cycles
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StoredSettings {
+    /// Touch calibration, if the unit was calibrated
+    pub calibration: Option<Calibration>,
+    /// Controller configuration
+    pub config: DeviceConfig,
+}
+
+impl StoredSettings {
+    /// Serialize into a record
+    pub fn to_bytes(&self) -> [u8; RECORD_LEN] {
+        let mut buf = [0; RECORD_LEN];
+        buf[0..4].copy_from_slice(&RECORD_MAGIC);
+        buf[4] = RECORD_VERSION;
+
+        let calibration = match &self.calibration {
+            Some(calibration) => {
+                buf[5] = FLAG_CALIBRATION;
+                *calibration
+            }
+            None => Calibration::IDENTITY,
+        };
+        for (chunk, coefficient) in buf[6..30]
+            .chunks_exact_mut(4)
+            .zip(calibration.coefficients())
+        {
+            chunk.copy_from_slice(&coefficient.to_le_bytes());
+        }
+
+        let config = &self.config;
+        let gesture = &config.gesture;
+        buf[30..43].copy_from_slice(&[
+            config.threshold,
+            config.filter_coefficient,
+            config.scan.active_rate,
+            config.scan.monitor_rate,
+            config.scan.monitor_timeout_s,
+            config.scan.keep_active as u8,
+            config.interrupt_mode.bits(),
+            gesture.radian,
+            gesture.offset_left_right,
+            gesture.offset_up_down,
+            gesture.distance_left_right,
+            gesture.distance_up_down,
+            gesture.distance_zoom,
+        ]);
+
+        let crc = crc32(&buf[..RECORD_LEN - 4]);
+        buf[RECORD_LEN - 4..].copy_from_slice(&crc.to_le_bytes());
+        buf
+    }
+
+    /// Decode a record, checking its magic, version and CRC
+    pub fn from_bytes(buf: &[u8; RECORD_LEN]) -> Result<Self, RecordError> {
+        if buf[0..4] != RECORD_MAGIC {
+            return Err(RecordError::BadMagic);
+        }
+        if buf[4] != RECORD_VERSION {
+            return Err(RecordError::UnsupportedVersion(buf[4]));
+        }
+        let crc = u32::from_le_bytes([buf[44], buf[45], buf[46], buf[47]]);
+        if crc != crc32(&buf[..RECORD_LEN - 4]) {
+            return Err(RecordError::BadCrc);
+        }
+
+        let mut coefficients = [0; 6];
+        for (coefficient, chunk) in coefficients.iter_mut().zip(buf[6..30].chunks_exact(4)) {
+            *coefficient = i32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let calibration =
+            (buf[5] & FLAG_CALIBRATION != 0).then(|| Calibration::from_coefficients(coefficients));
+
+        let scan = ScanConfig {
+            active_rate: buf[32],
+            monitor_rate: buf[33],
+            monitor_timeout_s: buf[34],
+            keep_active: match buf[35] {
+                0 => false,
+                1 => true,
+                _ => return Err(RecordError::InvalidValue),
+            },
+        };
+        scan.validate().map_err(|_| RecordError::InvalidValue)?;
+        let config = DeviceConfig {
+            threshold: buf[30],
+            filter_coefficient: buf[31],
+            scan,
+            interrupt_mode: InterruptMode::from_bits(buf[36]).ok_or(RecordError::InvalidValue)?,
+            gesture: GestureConfig {
+                radian: buf[37],
+                offset_left_right: buf[38],
+                offset_up_down: buf[39],
+                distance_left_right: buf[40],
+                distance_up_down: buf[41],
+                distance_zoom: buf[42],
+            },
+        };
+
+        Ok(StoredSettings {
+            calibration,
+            config,
+        })
+    }
+}
+
+/// Erase the sector at `offset` and write the settings record into it
+pub fn save<S: NorFlash>(
+    flash: &mut S,
+    offset: u32,
+    settings: &StoredSettings,
+) -> Result<(), StorageError<S::Error>> {
+    if !RECORD_CAPACITY.is_multiple_of(S::WRITE_SIZE) || S::ERASE_SIZE < RECORD_CAPACITY {
+        return Err(StorageError::Alignment);
+    }
+
+    let mut buf = [0xFF; RECORD_CAPACITY];
+    buf[..RECORD_LEN].copy_from_slice(&settings.to_bytes());
+    flash
+        .erase(offset, offset + S::ERASE_SIZE as u32)
+        .map_err(StorageError::Flash)?;
+    flash.write(offset, &buf).map_err(StorageError::Flash)
+}
+
+/// Read and decode the settings record at `offset`
+pub fn load<S: ReadNorFlash>(
+    flash: &mut S,
+    offset: u32,
+) -> Result<StoredSettings, StorageError<S::Error>> {
+    if !RECORD_CAPACITY.is_multiple_of(S::READ_SIZE) {
+        return Err(StorageError::Alignment);
+    }
+
+    let mut buf = [0; RECORD_CAPACITY];
+    flash.read(offset, &mut buf).map_err(StorageError::Flash)?;
+    let mut record = [0; RECORD_LEN];
+    record.copy_from_slice(&buf[..RECORD_LEN]);
+    StoredSettings::from_bytes(&record).map_err(StorageError::Record)
+}
+
+/// Init options and calibration from the record at `offset`, or `options`
+/// and no calibration when there is no valid record. The stored config
+/// replaces the one in `options`; an interrupt mode set in `options` still
+/// takes precedence over the stored one. The flag tells whether the record
+/// was used.
+pub(crate) fn restore<S: ReadNorFlash>(
+    flash: &mut S,
+    offset: u32,
+    options: &InitOptions,
+) -> (InitOptions, Option<Calibration>, bool) {
+    match load(flash, offset) {
+        Ok(settings) => (options.config(settings.config), settings.calibration, true),
+        Err(_) => (*options, None, false),
+    }
+}
+
+/// CRC-32 (IEEE 802.3), bitwise to keep the code small
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFF_u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
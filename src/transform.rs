@@ -0,0 +1,175 @@
+//! Mapping of controller coordinates onto display coordinates.
+//!
+//! The controller reports touches along its own axes. When the display is
+//! rotated or mounted mirrored, a [`Transform`] set on the driver re-maps
+//! every touch point so the application sees display coordinates.
+//!
+//! Mirroring and rotation flip coordinates against the panel size, so a
+//! driver with a transform also needs `with_panel_size`. Without one the
+//! panel spans the full 12 bit coordinate range.
+
+use crate::calibration::{Calibration, CalibrationError, Residual, Sample};
+use crate::constant::FT6X06_MAX_COORDINATE;
+
+/// Clockwise rotation of the display relative to the controller axes
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    /// Display and controller axes agree
+    #[default]
+    Deg0,
+    /// Display rotated by 90 degrees
+    Deg90,
+    /// Display rotated by 180 degrees
+    Deg180,
+    /// Display rotated by 270 degrees
+    Deg270,
+}
+
+/// Coordinate transform applied to every touch point.
+///
+/// The steps are applied in field order: axis swap, mirroring, rotation.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Transform {
+    /// Exchange the x and y axes
+    pub swap_xy: bool,
+    /// Mirror along the x axis, x becomes width - 1 - x
+    pub mirror_x: bool,
+    /// Mirror along the y axis, y becomes height - 1 - y
+    pub mirror_y: bool,
+    /// Rotation of the display
+    pub rotation: Rotation,
+}
+
+impl Transform {
+    /// Transform that only rotates
+    pub fn rotate(rotation: Rotation) -> Self {
+        Transform {
+            rotation,
+            ..Default::default()
+        }
+    }
+
+    /// Size of the transformed panel, given the panel size along the
+    /// controller axes
+    pub fn output_size(&self, width: u16, height: u16) -> (u16, u16) {
+        let (width, height) = if self.swap_xy {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        match self.rotation {
+            Rotation::Deg0 | Rotation::Deg180 => (width, height),
+            Rotation::Deg90 | Rotation::Deg270 => (height, width),
+        }
+    }
+
+    /// Map a point on a `width` x `height` panel, in controller axes, onto
+    /// the transformed panel. Points outside the panel are clamped to it
+    /// first; a zero width or height counts as 1.
+    pub fn apply(&self, x: u16, y: u16, width: u16, height: u16) -> (u16, u16) {
+        let (width, height) = (width.max(1), height.max(1));
+        let (x, y) = (x.min(width - 1), y.min(height - 1));
+        let (mut x, mut y, width, height) = if self.swap_xy {
+            (y, x, height, width)
+        } else {
+            (x, y, width, height)
+        };
+        if self.mirror_x {
+            x = width - 1 - x;
+        }
+        if self.mirror_y {
+            y = height - 1 - y;
+        }
+        match self.rotation {
+            Rotation::Deg0 => (x, y),
+            Rotation::Deg90 => (height - 1 - y, x),
+            Rotation::Deg180 => (width - 1 - x, height - 1 - y),
+            Rotation::Deg270 => (y, width - 1 - x),
+        }
+    }
+
+    /// Map a point on the transformed panel back onto the `width` x `height`
+    /// panel in controller axes; the inverse of [`apply`](Self::apply).
+    /// Points outside the transformed panel are clamped to it first; a zero
+    /// width or height counts as 1.
+    pub fn invert(&self, x: u16, y: u16, width: u16, height: u16) -> (u16, u16) {
+        let (width, height) = (width.max(1), height.max(1));
+        let (out_width, out_height) = self.output_size(width, height);
+        let (x, y) = (x.min(out_width - 1), y.min(out_height - 1));
+        let (width, height) = if self.swap_xy {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        let (mut x, mut y) = match self.rotation {
+            Rotation::Deg0 => (x, y),
+            Rotation::Deg90 => (y, height - 1 - x),
+            Rotation::Deg180 => (width - 1 - x, height - 1 - y),
+            Rotation::Deg270 => (width - 1 - y, x),
+        };
+        if self.mirror_x {
+            x = width - 1 - x;
+        }
+        if self.mirror_y {
+            y = height - 1 - y;
+        }
+        if self.swap_xy {
+            (y, x)
+        } else {
+            (x, y)
+        }
+    }
+}
+
+/// Everything that maps raw controller coordinates onto the display:
+/// calibration, clamping to the panel, transform
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Panel {
+    pub(crate) width: u16,
+    pub(crate) height: u16,
+    pub(crate) calibration: Option<Calibration>,
+    pub(crate) transform: Transform,
+}
+
+impl Panel {
+    /// The full 12 bit coordinate range, uncalibrated and untransformed:
+    /// clamping to it leaves every raw coordinate as it is
+    pub(crate) const DEFAULT: Panel = Panel {
+        width: FT6X06_MAX_COORDINATE + 1,
+        height: FT6X06_MAX_COORDINATE + 1,
+        calibration: None,
+        transform: Transform {
+            swap_xy: false,
+            mirror_x: false,
+            mirror_y: false,
+            rotation: Rotation::Deg0,
+        },
+    };
+
+    /// Map a raw controller point onto the display; the transform clamps it
+    /// to the panel
+    pub(crate) fn map(&self, x: u16, y: u16) -> (u16, u16) {
+        let (x, y) = match &self.calibration {
+            Some(calibration) => calibration.apply(x, y),
+            None => (x, y),
+        };
+        self.transform.apply(x, y, self.width, self.height)
+    }
+
+    /// Size of the panel as the application sees it
+    pub(crate) fn output_size(&self) -> (u16, u16) {
+        self.transform.output_size(self.width, self.height)
+    }
+
+    /// Fit a calibration to samples whose targets are display positions,
+    /// mapping the targets back through the transform first
+    pub(crate) fn fit_calibration(
+        &self,
+        samples: &[Sample],
+    ) -> Result<(Calibration, Residual), CalibrationError> {
+        Calibration::fit(samples, |sample| {
+            self.transform
+                .invert(sample.target_x, sample.target_y, self.width, self.height)
+        })
+    }
+}